@@ -0,0 +1,376 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, InvalidProbe, UnknownColumn};
+use crate::utils::{
+    check_path_present, open_file, print_table, read_metadata, read_thrift, FooterArgs,
+    ParquetFile, SizeFormatArgs, BLOOM_FILTER_HEADER_SIZE,
+};
+use clap::Parser;
+use log::debug;
+use parquet::basic::Type as PhysicalType;
+use parquet::bloom_filter::Sbbf;
+use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, Length, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::format::{
+    BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash, BloomFilterHeader,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Prints the bloom filters of Parquet file(s) and probes them for a value
+#[derive(Parser, Debug)]
+pub struct BloomCommandArgs {
+    /// Only show the bloom filters of the given column, as a dotted path
+    #[arg(short, long, value_name = "COLUMN")]
+    column: Option<String>,
+
+    /// Check whether the value might be in each row group, as it is stored in the
+    /// column, e.g. a number for integer columns. Use 0x followed by hex digits for
+    /// fixed length byte arrays.
+    #[arg(long, value_name = "VALUE", requires = "column")]
+    probe: Option<String>,
+
+    /// Print a JSON document with the bloom filters of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// A value to probe the bloom filters with, in the physical type of the column
+#[derive(Debug)]
+enum ProbeValue {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(Vec<u8>),
+}
+
+impl ProbeValue {
+    fn parse(value: &str, physical_type: PhysicalType) -> Result<ProbeValue, String> {
+        let invalid =
+            |e: &dyn std::fmt::Display| format!("{} is not a valid value: {}", value, e);
+        match physical_type {
+            PhysicalType::BOOLEAN => value
+                .parse()
+                .map(ProbeValue::Boolean)
+                .map_err(|e| invalid(&e)),
+            PhysicalType::INT32 => value
+                .parse()
+                .map(ProbeValue::Int32)
+                .map_err(|e| invalid(&e)),
+            PhysicalType::INT64 => value
+                .parse()
+                .map(ProbeValue::Int64)
+                .map_err(|e| invalid(&e)),
+            PhysicalType::FLOAT => value
+                .parse()
+                .map(ProbeValue::Float)
+                .map_err(|e| invalid(&e)),
+            PhysicalType::DOUBLE => value
+                .parse()
+                .map(ProbeValue::Double)
+                .map_err(|e| invalid(&e)),
+            PhysicalType::BYTE_ARRAY => Ok(ProbeValue::Bytes(value.as_bytes().to_vec())),
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => match value.strip_prefix("0x") {
+                Some(hex) => parse_hex(hex).map(ProbeValue::Bytes),
+                None => Ok(ProbeValue::Bytes(value.as_bytes().to_vec())),
+            },
+            PhysicalType::INT96 => Err("INT96 columns cannot be probed".to_string()),
+        }
+    }
+
+    fn might_be_in(&self, filter: &Sbbf) -> bool {
+        match self {
+            ProbeValue::Boolean(value) => filter.check(value),
+            ProbeValue::Int32(value) => filter.check(value),
+            ProbeValue::Int64(value) => filter.check(value),
+            ProbeValue::Float(value) => filter.check(value),
+            ProbeValue::Double(value) => filter.check(value),
+            ProbeValue::Bytes(value) => filter.check(value),
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err(format!("0x{} has an odd number of hex digits", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("0x{} is not a valid hex value", hex))
+        })
+        .collect()
+}
+
+/// The bloom filters of the column chunks of a file
+#[derive(Serialize, Debug)]
+struct FileBloomFilters {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probe: Option<String>,
+    chunks: Vec<ChunkBloomFilter>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChunkBloomFilter {
+    row_group: usize,
+    column: String,
+    bloom_filter: Option<BloomFilterInfo>,
+    /// Whether the probed value might be in the row group, which is always the case
+    /// when the column chunk has no bloom filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    might_contain: Option<bool>,
+}
+
+/// Where a bloom filter is and how it was built, as given by its header
+#[derive(Serialize, Debug)]
+struct BloomFilterInfo {
+    offset: i64,
+    /// The bytes of the header and the bitset
+    size: i64,
+    num_bytes: i32,
+    algorithm: &'static str,
+    hash: &'static str,
+    compression: &'static str,
+}
+
+pub(crate) fn execute(opts: BloomCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for (i, file_name) in opts.files.iter().enumerate() {
+        if i > 0 && !opts.json {
+            println!();
+        }
+        let file = open_file(file_name)?;
+        let filters =
+            read_bloom_filters(&file, &opts).map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&filters)?);
+        } else {
+            print_bloom_filters(&filters, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_bloom_filters(
+    file: &ParquetFile,
+    opts: &BloomCommandArgs,
+) -> Result<FileBloomFilters, PQRSError> {
+    let metadata = read_metadata(file, &opts.footer)?;
+    let schema = metadata.file_metadata().schema_descr();
+
+    let column_index = match &opts.column {
+        Some(column) => Some(
+            schema
+                .columns()
+                .iter()
+                .position(|c| &c.path().string() == column)
+                .ok_or_else(|| UnknownColumn(column.to_string()))?,
+        ),
+        None => None,
+    };
+    let probe = match (&opts.probe, column_index) {
+        (Some(value), Some(index)) => {
+            let column = schema.column(index);
+            let parsed =
+                ProbeValue::parse(value, column.physical_type()).map_err(|reason| {
+                    InvalidProbe {
+                        column: column.path().string(),
+                        reason,
+                    }
+                })?;
+            Some(parsed)
+        }
+        _ => None,
+    };
+
+    let mut chunks = vec![];
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        for (index, column) in row_group_metadata.columns().iter().enumerate() {
+            if column_index.is_some() && column_index != Some(index) {
+                continue;
+            }
+            let might_contain = match &probe {
+                Some(probe) => Some(
+                    read_bloom_filter(file, row_group_metadata, index)?
+                        .map_or(true, |filter| probe.might_be_in(&filter)),
+                ),
+                None => None,
+            };
+            chunks.push(ChunkBloomFilter {
+                row_group,
+                column: column.column_path().string(),
+                bloom_filter: read_bloom_filter_info(file, column)?,
+                might_contain,
+            });
+        }
+    }
+
+    Ok(FileBloomFilters {
+        file: file.path().display().to_string(),
+        probe: opts.probe.clone(),
+        chunks,
+    })
+}
+
+/// Read the bloom filter of a column chunk. The row group reader reads the bloom
+/// filters of all of its columns, so the others are left out of the metadata it gets.
+fn read_bloom_filter(
+    file: &ParquetFile,
+    row_group: &RowGroupMetaData,
+    index: usize,
+) -> Result<Option<Sbbf>, PQRSError> {
+    let columns = row_group
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let builder = column.clone().into_builder();
+            if i == index {
+                builder.build()
+            } else {
+                builder
+                    .set_bloom_filter_offset(None)
+                    .set_bloom_filter_length(None)
+                    .build()
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let metadata = row_group
+        .clone()
+        .into_builder()
+        .set_column_metadata(columns)
+        .build()?;
+    let properties = ReaderProperties::builder()
+        .set_read_bloom_filter(true)
+        .build();
+    let reader = SerializedRowGroupReader::new(
+        Arc::new(file.try_clone()?),
+        &metadata,
+        None,
+        Arc::new(properties),
+    )?;
+    Ok(reader.get_column_bloom_filter(index).cloned())
+}
+
+fn read_bloom_filter_info(
+    file: &ParquetFile,
+    column: &ColumnChunkMetaData,
+) -> Result<Option<BloomFilterInfo>, PQRSError> {
+    let offset = match column.bloom_filter_offset() {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    let length =
+        (file.len().saturating_sub(offset as u64) as usize).min(BLOOM_FILTER_HEADER_SIZE);
+    let bytes = file.get_bytes(offset as u64, length)?;
+    let (header, header_length) = read_thrift::<BloomFilterHeader>(&bytes)?;
+
+    Ok(Some(BloomFilterInfo {
+        offset,
+        size: column
+            .bloom_filter_length()
+            .map_or(header_length as i64 + header.num_bytes as i64, |l| l as i64),
+        num_bytes: header.num_bytes,
+        algorithm: match header.algorithm {
+            BloomFilterAlgorithm::BLOCK(_) => "SPLIT_BLOCK",
+        },
+        hash: match header.hash {
+            BloomFilterHash::XXHASH(_) => "XXHASH",
+        },
+        compression: match header.compression {
+            BloomFilterCompression::UNCOMPRESSED(_) => "UNCOMPRESSED",
+        },
+    }))
+}
+
+fn print_bloom_filters(filters: &FileBloomFilters, format: SizeFormatArgs) {
+    println!("File Name: {}", filters.file);
+
+    let mut header = vec![
+        "Row Group".to_string(),
+        "Column".to_string(),
+        "Offset".to_string(),
+        "Size".to_string(),
+        "Algorithm".to_string(),
+        "Hash".to_string(),
+        "Compression".to_string(),
+    ];
+    if let Some(probe) = &filters.probe {
+        header.push(format!("Contains {}", probe));
+    }
+    let mut rows = vec![header];
+    for chunk in &filters.chunks {
+        let mut row = match &chunk.bloom_filter {
+            Some(filter) => vec![
+                chunk.row_group.to_string(),
+                chunk.column.to_string(),
+                filter.offset.to_string(),
+                format.format(filter.size),
+                filter.algorithm.to_string(),
+                filter.hash.to_string(),
+                filter.compression.to_string(),
+            ],
+            None => vec![
+                chunk.row_group.to_string(),
+                chunk.column.to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+            ],
+        };
+        match (chunk.might_contain, &chunk.bloom_filter) {
+            (Some(true), Some(_)) => row.push("maybe".to_string()),
+            (Some(true), None) => row.push("maybe, no bloom filter".to_string()),
+            (Some(false), _) => row.push("no".to_string()),
+            (None, _) => {}
+        }
+        rows.push(row);
+    }
+    print_table(
+        &rows,
+        &[true, false, true, true, false, false, false, false],
+    );
+
+    if let Some(probe) = &filters.probe {
+        let row_groups = filters
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.might_contain == Some(true))
+            .count();
+        println!(
+            "{} might be in {} of {} row groups",
+            probe,
+            row_groups,
+            filters.chunks.len()
+        );
+    }
+}
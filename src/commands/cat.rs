@@ -1,7 +1,12 @@
 use crate::errors::PQRSError;
-use crate::errors::PQRSError::FileNotFound;
+use crate::errors::PQRSError::{CouldNotOpenFile, FileNotFound};
+use crate::progress::Progress;
+use crate::remote::is_remote;
+use crate::render::{RenderArgs, Renderer};
 use crate::utils::Formats;
-use crate::utils::{check_path_present, is_hidden, open_file, print_rows};
+use crate::utils::{
+    check_path_present, is_hidden, is_stdio, open_file, print_rows, PrintOptions, Skipped,
+};
 use clap::Parser;
 use log::debug;
 use std::fs::metadata;
@@ -23,11 +28,32 @@ pub struct CatCommandArgs {
     #[arg(short, long, conflicts_with = "csv")]
     json: bool,
 
-    /// Parquet files or folders to read from
+    /// Number of row groups to decode in parallel, 0 uses one thread per CPU
+    #[arg(long, default_value = "1")]
+    jobs: usize,
+
+    /// Show the progress of each file on stderr, when it is a terminal
+    #[arg(long)]
+    progress: bool,
+
+    /// Do not print the name of each file on stderr before its contents
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Skip row groups that cannot be read instead of stopping, and print a summary
+    /// of what was skipped at the end
+    #[arg(long)]
+    skip_corrupt: bool,
+
+    #[command(flatten)]
+    render: RenderArgs,
+
+    /// Parquet files or folders to read from, use - to read from stdin
     locations: Vec<PathBuf>,
 }
 
 pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
+    let renderer = Renderer::try_new(&opts.render)?;
     let format = if opts.json {
         Formats::Json
     } else if opts.csv_no_header {
@@ -46,7 +72,12 @@ pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
     let mut directories = vec![];
     let mut files = linked_hash_set::LinkedHashSet::new();
     for location in &opts.locations {
-        let meta = metadata(location).unwrap();
+        if is_stdio(location) || is_remote(location) {
+            files.insert(location.clone());
+            continue;
+        }
+        let meta =
+            metadata(location).map_err(|_| FileNotFound(location.to_path_buf()))?;
         if meta.is_dir() {
             directories.push(location.clone());
         }
@@ -63,7 +94,7 @@ pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
         {
             debug!("{}", entry.path().display());
             let path = entry.path().to_path_buf();
-            let meta = metadata(&path).unwrap();
+            let meta = metadata(&path).map_err(|_| CouldNotOpenFile(path.clone()))?;
             if meta.is_file() {
                 files.insert(path);
             }
@@ -80,15 +111,28 @@ pub(crate) fn execute(opts: CatCommandArgs) -> Result<(), PQRSError> {
         }
     }
 
+    let mut skipped = Skipped::new(opts.skip_corrupt);
     for file_name in &files {
         let file = open_file(file_name)?;
-        let info_string = format!("File: {}", file_name.display());
-        let length = info_string.len();
-        eprintln!("\n{}", "#".repeat(length));
-        eprintln!("{}", info_string);
-        eprintln!("{}\n", "#".repeat(length));
-        print_rows(file, None, format)?;
+        if !opts.quiet {
+            let info_string = format!("File: {}", file_name.display());
+            let length = info_string.len();
+            eprintln!("\n{}", "#".repeat(length));
+            eprintln!("{}", info_string);
+            eprintln!("{}\n", "#".repeat(length));
+        }
+        let mut progress = Progress::new(opts.progress);
+        progress.add_file(&file).map_err(|e| e.in_file(file_name))?;
+        let options = PrintOptions {
+            format,
+            render: &renderer,
+            jobs: opts.jobs,
+        };
+        print_rows(file, None, options, &mut progress, &mut skipped)
+            .map_err(|e| e.in_file(file_name))?;
+        progress.finish();
     }
+    skipped.print_summary();
 
     Ok(())
 }
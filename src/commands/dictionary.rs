@@ -0,0 +1,544 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{
+    check_path_present, encoding_name, format_plain_value, open_file, print_table,
+    read_metadata, read_page_headers, FooterArgs, ParquetFile, SizeFormatArgs,
+};
+use clap::Parser;
+use log::debug;
+use parquet::basic::Type as PhysicalType;
+use parquet::column::reader::{ColumnReader, ColumnReaderImpl};
+use parquet::compression::{create_codec, CodecOptions};
+use parquet::data_type::{AsBytes, DataType};
+use parquet::errors::ParquetError;
+use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{ChunkReader, RowGroupReader};
+use parquet::file::serialized_reader::SerializedRowGroupReader;
+use parquet::format::{self, PageHeader};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The number of records to read at a time when counting the values of a column chunk
+const BATCH_SIZE: usize = 8192;
+
+/// Prints the dictionaries of the column chunks in Parquet file(s)
+#[derive(Parser, Debug)]
+pub struct DictionaryCommandArgs {
+    /// Only show the dictionaries of the given columns, as dotted paths, e.g. country.name
+    #[arg(short, long, value_name = "COLUMN")]
+    column: Vec<String>,
+
+    /// Only show the dictionaries of the given row groups
+    #[arg(short, long, value_name = "ROW_GROUP")]
+    row_group: Vec<usize>,
+
+    /// Number of dictionary values to print per column chunk, 0 prints all of them
+    #[arg(short, long, default_value = "10")]
+    limit: usize,
+
+    /// Only decode the dictionaries, without reading the data pages to count how often
+    /// each value is used
+    #[arg(long)]
+    no_counts: bool,
+
+    /// Print a JSON document with the dictionaries of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The dictionaries of the column chunks of a file
+#[derive(Serialize, Debug)]
+struct FileDictionaries {
+    file: String,
+    chunks: Vec<ChunkDictionary>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChunkDictionary {
+    row_group: usize,
+    column: String,
+    dictionary: Option<DictionaryPage>,
+    /// The number of data pages whose values are indexes into the dictionary
+    dictionary_pages: usize,
+    /// The number of data pages written without the dictionary, which writers fall
+    /// back to once the dictionary grows past its size limit
+    fallback_pages: usize,
+    fallback_encodings: Vec<String>,
+    fell_back: bool,
+    /// The number of values in the pages that fell back, which are not counted
+    /// towards the dictionary values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fallback_values: Option<u64>,
+    /// The number of dictionary values left out because of the limit
+    omitted_values: usize,
+    values: Vec<DictionaryValue>,
+}
+
+/// What the header of a dictionary page says about it
+#[derive(Serialize, Debug)]
+struct DictionaryPage {
+    offset: u64,
+    encoding: String,
+    num_values: i32,
+    compressed_size: i32,
+    uncompressed_size: i32,
+    is_sorted: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+struct DictionaryValue {
+    index: usize,
+    value: String,
+    /// The number of non-null values in the dictionary encoded pages that refer to
+    /// this dictionary value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u64>,
+}
+
+/// The number of levels of a data page and whether its values are dictionary indexes
+struct DataPage {
+    num_levels: u64,
+    dictionary_encoded: bool,
+}
+
+pub(crate) fn execute(opts: DictionaryCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for (i, file_name) in opts.files.iter().enumerate() {
+        if i > 0 && !opts.json {
+            println!();
+        }
+        let file = open_file(file_name)?;
+        let dictionaries =
+            read_file_dictionaries(&file, &opts).map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&dictionaries)?);
+        } else {
+            print_dictionaries(&dictionaries, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_file_dictionaries(
+    file: &ParquetFile,
+    opts: &DictionaryCommandArgs,
+) -> Result<FileDictionaries, PQRSError> {
+    let metadata = read_metadata(file, &opts.footer)?;
+    let schema = metadata.file_metadata().schema_descr();
+    for column in &opts.column {
+        if !schema
+            .columns()
+            .iter()
+            .any(|c| &c.path().string() == column)
+        {
+            return Err(UnknownColumn(column.to_string()));
+        }
+    }
+
+    let mut chunks = vec![];
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        if !opts.row_group.is_empty() && !opts.row_group.contains(&row_group) {
+            continue;
+        }
+        for (index, column) in row_group_metadata.columns().iter().enumerate() {
+            let path = column.column_path().string();
+            if !opts.column.is_empty() && !opts.column.contains(&path) {
+                continue;
+            }
+            chunks.push(read_chunk_dictionary(
+                file,
+                row_group_metadata,
+                row_group,
+                index,
+                opts,
+            )?);
+        }
+    }
+
+    Ok(FileDictionaries {
+        file: file.path().display().to_string(),
+        chunks,
+    })
+}
+
+fn read_chunk_dictionary(
+    file: &ParquetFile,
+    row_group_metadata: &RowGroupMetaData,
+    row_group: usize,
+    index: usize,
+    opts: &DictionaryCommandArgs,
+) -> Result<ChunkDictionary, PQRSError> {
+    let column = row_group_metadata.column(index);
+    let headers = read_page_headers(file, column)?;
+
+    let mut dictionary = None;
+    let mut data_pages = vec![];
+    let mut fallback_encodings: Vec<String> = vec![];
+    for (offset, header, header_size) in &headers {
+        if let Some(dictionary_header) = &header.dictionary_page_header {
+            if dictionary.is_none() {
+                dictionary = Some((*offset, *header_size, header, dictionary_header));
+            }
+            continue;
+        }
+        let (num_values, encoding) =
+            match (&header.data_page_header, &header.data_page_header_v2) {
+                (Some(data), _) => (data.num_values, data.encoding),
+                (None, Some(data)) => (data.num_values, data.encoding),
+                (None, None) => continue,
+            };
+        let dictionary_encoded = encoding == format::Encoding::PLAIN_DICTIONARY
+            || encoding == format::Encoding::RLE_DICTIONARY;
+        if !dictionary_encoded && !fallback_encodings.contains(&encoding_name(encoding)) {
+            fallback_encodings.push(encoding_name(encoding));
+        }
+        data_pages.push(DataPage {
+            num_levels: num_values.max(0) as u64,
+            dictionary_encoded,
+        });
+    }
+
+    let dictionary_pages = data_pages.iter().filter(|p| p.dictionary_encoded).count();
+    let mut chunk = ChunkDictionary {
+        row_group,
+        column: column.column_path().string(),
+        dictionary: None,
+        dictionary_pages,
+        fallback_pages: data_pages.len() - dictionary_pages,
+        fallback_encodings,
+        fell_back: false,
+        fallback_values: None,
+        omitted_values: 0,
+        values: vec![],
+    };
+    let (offset, header_size, header, dictionary_header) = match dictionary {
+        Some(dictionary) => dictionary,
+        None => return Ok(chunk),
+    };
+    chunk.fell_back = chunk.fallback_pages > 0;
+    chunk.dictionary = Some(DictionaryPage {
+        offset,
+        encoding: encoding_name(dictionary_header.encoding),
+        num_values: dictionary_header.num_values,
+        compressed_size: header.compressed_page_size,
+        uncompressed_size: header.uncompressed_page_size,
+        is_sorted: dictionary_header.is_sorted,
+    });
+
+    let page = file.get_bytes(
+        offset + header_size as u64,
+        header.compressed_page_size.max(0) as usize,
+    )?;
+    let page = decompress_dictionary(column, header, &page)?;
+    let entries = decode_plain(
+        column.column_type(),
+        column.column_descr().type_length(),
+        &page,
+        dictionary_header.num_values.max(0) as usize,
+    )
+    .map_err(|e| {
+        ParquetError::General(format!(
+            "Could not decode the dictionary at offset {} of column {}: {}",
+            offset,
+            column.column_path().string(),
+            e
+        ))
+    })?;
+
+    let counts = if opts.no_counts {
+        None
+    } else {
+        let reader = SerializedRowGroupReader::new(
+            Arc::new(file.try_clone()?),
+            row_group_metadata,
+            None,
+            Arc::new(ReaderProperties::builder().build()),
+        )?;
+        let (counts, fallback_values) =
+            count_values(&reader, index, column, &entries, &data_pages)?;
+        chunk.fallback_values = Some(fallback_values);
+        Some(counts)
+    };
+
+    let mut values = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| DictionaryValue {
+            index,
+            value: format_plain_value(column.column_type(), entry),
+            count: counts.as_ref().map(|counts| counts[index]),
+        })
+        .collect::<Vec<_>>();
+    // the most used values first, keeping the dictionary order between equal counts
+    values.sort_by_key(|value| Reverse(value.count));
+    if opts.limit > 0 && values.len() > opts.limit {
+        chunk.omitted_values = values.len() - opts.limit;
+        values.truncate(opts.limit);
+    }
+    chunk.values = values;
+
+    Ok(chunk)
+}
+
+fn decompress_dictionary(
+    column: &ColumnChunkMetaData,
+    header: &PageHeader,
+    page: &[u8],
+) -> Result<Vec<u8>, PQRSError> {
+    match create_codec(column.compression(), &CodecOptions::default())? {
+        Some(mut codec) => {
+            let mut decompressed = Vec::new();
+            codec.decompress(
+                page,
+                &mut decompressed,
+                Some(header.uncompressed_page_size.max(0) as usize),
+            )?;
+            Ok(decompressed)
+        }
+        None => Ok(page.to_vec()),
+    }
+}
+
+/// Split PLAIN encoded values into the bytes of each value, as they are stored for
+/// the statistics, leaving out the length prefix of byte arrays
+fn decode_plain(
+    physical_type: PhysicalType,
+    type_length: i32,
+    data: &[u8],
+    num_values: usize,
+) -> Result<Vec<Vec<u8>>, String> {
+    if physical_type == PhysicalType::BOOLEAN {
+        if data.len() * 8 < num_values {
+            return Err(format!(
+                "{} bytes cannot hold {} booleans",
+                data.len(),
+                num_values
+            ));
+        }
+        return Ok((0..num_values)
+            .map(|i| vec![(data[i / 8] >> (i % 8)) & 1])
+            .collect());
+    }
+
+    // the number of values comes from the page header, so reserve no more than the
+    // values the data can hold
+    let mut values = Vec::with_capacity(num_values.min(data.len()));
+    let mut position = 0;
+    for i in 0..num_values {
+        let length = match physical_type {
+            PhysicalType::INT32 | PhysicalType::FLOAT => 4,
+            PhysicalType::INT64 | PhysicalType::DOUBLE => 8,
+            PhysicalType::INT96 => 12,
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => type_length.max(0) as usize,
+            _ => {
+                let prefix = data
+                    .get(position..position + 4)
+                    .ok_or_else(|| format!("value {} is cut off", i))?;
+                position += 4;
+                u32::from_le_bytes(prefix.try_into().unwrap()) as usize
+            }
+        };
+        let value = data
+            .get(position..position + length)
+            .ok_or_else(|| format!("value {} is cut off", i))?;
+        values.push(value.to_vec());
+        position += length;
+    }
+
+    Ok(values)
+}
+
+/// Count how often each dictionary value is used by reading the values of the column
+/// chunk, returning the counts and the number of values in pages that fell back
+fn count_values(
+    reader: &dyn RowGroupReader,
+    index: usize,
+    column: &ColumnChunkMetaData,
+    entries: &[Vec<u8>],
+    data_pages: &[DataPage],
+) -> Result<(Vec<u64>, u64), PQRSError> {
+    let mut counter = Counter::new(column, entries, data_pages);
+    match reader.get_column_reader(index)? {
+        ColumnReader::BoolColumnReader(r) => counter.read(r)?,
+        ColumnReader::Int32ColumnReader(r) => counter.read(r)?,
+        ColumnReader::Int64ColumnReader(r) => counter.read(r)?,
+        ColumnReader::Int96ColumnReader(r) => counter.read(r)?,
+        ColumnReader::FloatColumnReader(r) => counter.read(r)?,
+        ColumnReader::DoubleColumnReader(r) => counter.read(r)?,
+        ColumnReader::ByteArrayColumnReader(r) => counter.read(r)?,
+        ColumnReader::FixedLenByteArrayColumnReader(r) => counter.read(r)?,
+    }
+    Ok((counter.counts, counter.fallback_values))
+}
+
+/// Counts the values of a column chunk, telling apart values from dictionary encoded
+/// pages and pages that fell back by how many levels were read so far
+struct Counter<'a> {
+    max_def_level: i16,
+    max_rep_level: i16,
+    positions: HashMap<&'a [u8], usize>,
+    data_pages: &'a [DataPage],
+    counts: Vec<u64>,
+    fallback_values: u64,
+    page: usize,
+    page_end: u64,
+    levels_read: u64,
+}
+
+impl<'a> Counter<'a> {
+    fn new(
+        column: &ColumnChunkMetaData,
+        entries: &'a [Vec<u8>],
+        data_pages: &'a [DataPage],
+    ) -> Self {
+        let mut positions = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            positions.entry(entry.as_slice()).or_insert(index);
+        }
+        Counter {
+            max_def_level: column.column_descr().max_def_level(),
+            max_rep_level: column.column_descr().max_rep_level(),
+            positions,
+            data_pages,
+            counts: vec![0; entries.len()],
+            fallback_values: 0,
+            page: 0,
+            page_end: data_pages.first().map_or(0, |p| p.num_levels),
+            levels_read: 0,
+        }
+    }
+
+    fn read<T: DataType>(
+        &mut self,
+        mut reader: ColumnReaderImpl<T>,
+    ) -> Result<(), PQRSError> {
+        let mut def_levels = vec![];
+        let mut rep_levels = vec![];
+        let mut values = vec![];
+        loop {
+            def_levels.clear();
+            rep_levels.clear();
+            values.clear();
+            let (records, _, levels) = reader.read_records(
+                BATCH_SIZE,
+                (self.max_def_level > 0).then_some(&mut def_levels),
+                (self.max_rep_level > 0).then_some(&mut rep_levels),
+                &mut values,
+            )?;
+            if records == 0 && levels == 0 {
+                return Ok(());
+            }
+
+            let mut values = values.iter();
+            for level in 0..levels {
+                let has_value = self.max_def_level == 0
+                    || def_levels.get(level) == Some(&self.max_def_level);
+                let dictionary_encoded = self.next_level();
+                if !has_value {
+                    continue;
+                }
+                let value = match values.next() {
+                    Some(value) => value,
+                    None => break,
+                };
+                match self.positions.get(value.as_bytes()) {
+                    Some(&index) if dictionary_encoded => self.counts[index] += 1,
+                    _ => self.fallback_values += 1,
+                }
+            }
+        }
+    }
+
+    /// Move on to the next level, returning whether it is in a dictionary encoded page
+    fn next_level(&mut self) -> bool {
+        while self.levels_read >= self.page_end && self.page + 1 < self.data_pages.len() {
+            self.page += 1;
+            self.page_end += self.data_pages[self.page].num_levels;
+        }
+        self.levels_read += 1;
+        self.data_pages
+            .get(self.page)
+            .map_or(true, |page| page.dictionary_encoded)
+    }
+}
+
+fn print_dictionaries(dictionaries: &FileDictionaries, format: SizeFormatArgs) {
+    println!("File Name: {}", dictionaries.file);
+    for chunk in &dictionaries.chunks {
+        println!();
+        println!("Row Group {}, Column {}:", chunk.row_group, chunk.column);
+        let dictionary = match &chunk.dictionary {
+            Some(dictionary) => dictionary,
+            None => {
+                println!("Not dictionary encoded");
+                continue;
+            }
+        };
+
+        println!(
+            "Dictionary: {} values ({}), offset {}, size {}",
+            dictionary.num_values,
+            dictionary.encoding,
+            dictionary.offset,
+            format.format(dictionary.uncompressed_size as i64)
+        );
+        if chunk.fell_back {
+            println!(
+                "Fell back to {} after {} of {} data pages",
+                chunk.fallback_encodings.join(", "),
+                chunk.dictionary_pages,
+                chunk.dictionary_pages + chunk.fallback_pages
+            );
+        } else {
+            println!(
+                "All {} data pages use the dictionary",
+                chunk.dictionary_pages
+            );
+        }
+
+        let counted = chunk.values.iter().any(|value| value.count.is_some());
+        let mut rows = vec![vec!["Index".to_string(), "Value".to_string()]];
+        if counted {
+            rows[0].push("Count".to_string());
+        }
+        for value in &chunk.values {
+            let mut row = vec![value.index.to_string(), value.value.clone()];
+            if let Some(count) = value.count {
+                row.push(count.to_string());
+            }
+            rows.push(row);
+        }
+        print_table(&rows, &[true, false, true]);
+        if chunk.omitted_values > 0 {
+            println!("... and {} more values", chunk.omitted_values);
+        }
+        if let Some(fallback_values) = chunk.fallback_values.filter(|_| chunk.fell_back) {
+            println!("{} values in pages that fell back", fallback_values);
+        }
+    }
+}
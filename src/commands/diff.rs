@@ -0,0 +1,470 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, FilesDiffer, UnknownKeyColumn};
+use crate::utils::{
+    check_path_present, open_file, read_metadata, read_rows_json, FooterArgs, ParquetFile,
+};
+use arrow::datatypes::{DataType, Field, Fields, Schema};
+use clap::Parser;
+use log::debug;
+use parquet::arrow::{parquet_to_arrow_schema, ARROW_SCHEMA_META_KEY};
+use parquet::file::metadata::ParquetMetaData;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Compares the schema, metadata, row count and data of two Parquet files
+#[derive(Parser, Debug)]
+pub struct DiffCommandArgs {
+    /// Compare the rows of the files as well, by their position unless --key is given
+    #[arg(short, long)]
+    data: bool,
+
+    /// Comma separated columns that identify a row, rows with the same values in these
+    /// columns are compared with each other. Implies --data
+    #[arg(short, long, value_delimiter = ',')]
+    key: Vec<String>,
+
+    /// Largest difference between two numbers that are still considered equal
+    #[arg(short, long, default_value = "0")]
+    tolerance: f64,
+
+    /// Number of row differences to print
+    #[arg(short, long, default_value = "10")]
+    limit: usize,
+
+    /// Parquet file to compare from, use - to read from stdin
+    first: PathBuf,
+
+    /// Parquet file to compare to
+    second: PathBuf,
+}
+
+/// A difference in a named item, such as a field of the schema or a metadata key
+enum Change {
+    Removed(String, String),
+    Added(String, String),
+    /// The name and a description of the change
+    Changed(String, String),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Removed(name, value) => write!(f, "- {}: {}", name, value),
+            Change::Added(name, value) => write!(f, "+ {}: {}", name, value),
+            Change::Changed(name, change) => write!(f, "~ {}: {}", name, change),
+        }
+    }
+}
+
+/// The changes found in a part of the files. Only the first changes are kept when
+/// there is a limit, the others are only counted.
+struct Changes {
+    kept: Vec<Change>,
+    limit: Option<usize>,
+    removed: usize,
+    added: usize,
+    changed: usize,
+}
+
+impl Changes {
+    fn new(limit: Option<usize>) -> Changes {
+        Changes {
+            kept: vec![],
+            limit,
+            removed: 0,
+            added: 0,
+            changed: 0,
+        }
+    }
+
+    fn push(&mut self, change: Change) {
+        match change {
+            Change::Removed(..) => self.removed += 1,
+            Change::Added(..) => self.added += 1,
+            Change::Changed(..) => self.changed += 1,
+        }
+        if self.limit.map_or(true, |limit| self.kept.len() < limit) {
+            self.kept.push(change);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.removed + self.added + self.changed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Print the section title with a summary of the changes, followed by the changes
+    fn print(&self, title: &str) {
+        if self.is_empty() {
+            println!("{}: identical", title);
+            return;
+        }
+        println!(
+            "{}: {} removed, {} added, {} changed",
+            title, self.removed, self.added, self.changed
+        );
+
+        for change in &self.kept {
+            println!("  {}", change);
+        }
+        if self.len() > self.kept.len() {
+            println!("  ... and {} more", self.len() - self.kept.len());
+        }
+    }
+}
+
+fn describe_field(field: &Field) -> String {
+    if field.is_nullable() {
+        describe_type(field.data_type())
+    } else {
+        format!("{} not null", describe_type(field.data_type()))
+    }
+}
+
+/// Describe the data type, writing nested types as e.g. `Struct<name: Utf8, ids: List<Int64>>`
+fn describe_type(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name(), describe_field(field)))
+                .collect::<Vec<_>>();
+            format!("Struct<{}>", fields.join(", "))
+        }
+        DataType::List(field) => format!("List<{}>", describe_field(field)),
+        DataType::LargeList(field) => format!("LargeList<{}>", describe_field(field)),
+        DataType::FixedSizeList(field, size) => {
+            format!("FixedSizeList<{}; {}>", describe_field(field), size)
+        }
+        DataType::Map(field, _) => format!("Map<{}>", describe_field(field)),
+        _ => data_type.to_string(),
+    }
+}
+
+/// Compare the fields by name, descending into the fields of structs
+fn diff_fields(prefix: &str, first: &Fields, second: &Fields, changes: &mut Changes) {
+    for field in first.iter() {
+        let name = format!("{}{}", prefix, field.name());
+        match second.find(field.name()) {
+            None => changes.push(Change::Removed(name, describe_field(field))),
+            Some((_, other)) => match (field.data_type(), other.data_type()) {
+                (DataType::Struct(children), DataType::Struct(other_children))
+                    if field.is_nullable() == other.is_nullable() =>
+                {
+                    diff_fields(&format!("{}.", name), children, other_children, changes)
+                }
+                _ if field.data_type() != other.data_type()
+                    || field.is_nullable() != other.is_nullable() =>
+                {
+                    let change =
+                        format!("{} -> {}", describe_field(field), describe_field(other));
+                    changes.push(Change::Changed(name, change))
+                }
+                _ => {}
+            },
+        }
+    }
+    for field in second.iter() {
+        if first.find(field.name()).is_none() {
+            let name = format!("{}{}", prefix, field.name());
+            changes.push(Change::Added(name, describe_field(field)));
+        }
+    }
+}
+
+/// The key value metadata of the file, without the arrow schema which is compared as
+/// part of the schema
+fn key_value_metadata(metadata: &ParquetMetaData) -> BTreeMap<String, String> {
+    metadata
+        .file_metadata()
+        .key_value_metadata()
+        .into_iter()
+        .flatten()
+        .filter(|kv| kv.key != ARROW_SCHEMA_META_KEY)
+        .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
+        .collect()
+}
+
+fn diff_metadata(first: &ParquetMetaData, second: &ParquetMetaData) -> Changes {
+    let first = key_value_metadata(first);
+    let second = key_value_metadata(second);
+
+    let mut changes = Changes::new(None);
+    for (key, value) in &first {
+        match second.get(key) {
+            None => changes.push(Change::Removed(key.clone(), value.clone())),
+            Some(other) if other != value => {
+                let change = format!("{} -> {}", value, other);
+                changes.push(Change::Changed(key.clone(), change))
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, value) in &second {
+        if !first.contains_key(key) {
+            changes.push(Change::Added(key.clone(), value.clone()));
+        }
+    }
+    changes
+}
+
+/// Compare two json values, allowing numbers to differ by the tolerance
+fn values_equal(first: &Value, second: &Value, tolerance: f64) -> bool {
+    match (first, second) {
+        (Value::Number(a), Value::Number(b)) => {
+            a == b
+                || match (a.as_f64(), b.as_f64()) {
+                    (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+                    _ => false,
+                }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| values_equal(a, b, tolerance))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, a)| {
+                    b.get(key).is_some_and(|b| values_equal(a, b, tolerance))
+                })
+        }
+        _ => first == second,
+    }
+}
+
+fn describe_value(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::from("(missing)"),
+    }
+}
+
+/// Compares rows, printing the columns that differ in the order of the schemas
+struct RowComparer {
+    /// The columns of the first file followed by those only in the second file
+    columns: Vec<String>,
+    tolerance: f64,
+}
+
+impl RowComparer {
+    fn new(first: &Schema, second: &Schema, tolerance: f64) -> RowComparer {
+        let mut columns = first
+            .fields()
+            .iter()
+            .map(|field| field.name().to_owned())
+            .collect::<Vec<_>>();
+        for field in second.fields() {
+            if !columns.contains(field.name()) {
+                columns.push(field.name().to_owned());
+            }
+        }
+        RowComparer { columns, tolerance }
+    }
+
+    /// The columns whose values differ between the rows, as `column: from -> to`
+    fn changed_columns(&self, first: &Value, second: &Value) -> Option<String> {
+        if values_equal(first, second, self.tolerance) {
+            return None;
+        }
+
+        let changes = self
+            .columns
+            .iter()
+            .filter_map(|column| {
+                let (a, b) = (first.get(column), second.get(column));
+                let equal = match (a, b) {
+                    (Some(a), Some(b)) => values_equal(a, b, self.tolerance),
+                    (a, b) => a == b,
+                };
+                (!equal).then(|| {
+                    format!("{}: {} -> {}", column, describe_value(a), describe_value(b))
+                })
+            })
+            .collect::<Vec<_>>();
+        Some(changes.join(", "))
+    }
+}
+
+/// Compare the rows at the same positions in both files, reading them one at a time
+fn diff_rows_by_position(
+    mut first: impl Iterator<Item = Result<Value, PQRSError>>,
+    mut second: impl Iterator<Item = Result<Value, PQRSError>>,
+    comparer: &RowComparer,
+    changes: &mut Changes,
+) -> Result<(), PQRSError> {
+    for index in 0.. {
+        let name = format!("row {}", index);
+        match (first.next().transpose()?, second.next().transpose()?) {
+            (Some(a), Some(b)) => {
+                if let Some(columns) = comparer.changed_columns(&a, &b) {
+                    changes.push(Change::Changed(name, columns));
+                }
+            }
+            (Some(row), None) => changes.push(Change::Removed(name, row.to_string())),
+            (None, Some(row)) => changes.push(Change::Added(name, row.to_string())),
+            (None, None) => break,
+        }
+    }
+    Ok(())
+}
+
+/// Describe the values of the key columns of the row, e.g. `id=3, name="x"`
+fn row_key(row: &Value, key: &[String]) -> String {
+    key.iter()
+        .map(|column| format!("{}={}", column, describe_value(row.get(column))))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Compare the rows that have the same values in the key columns. Rows with the same
+/// key are paired in the order they appear in the files. The rows of the first file
+/// are kept by their key, while those of the second file are read one at a time.
+fn diff_rows_by_key(
+    first: impl Iterator<Item = Result<Value, PQRSError>>,
+    second: impl Iterator<Item = Result<Value, PQRSError>>,
+    key: &[String],
+    comparer: &RowComparer,
+    changes: &mut Changes,
+) -> Result<(), PQRSError> {
+    let mut unmatched: HashMap<String, VecDeque<(usize, Value)>> = HashMap::new();
+    for (index, row) in first.enumerate() {
+        let row = row?;
+        unmatched
+            .entry(row_key(&row, key))
+            .or_default()
+            .push_back((index, row));
+    }
+
+    for row in second {
+        let row = row?;
+        let name = row_key(&row, key);
+        match unmatched.get_mut(&name).and_then(|rows| rows.pop_front()) {
+            Some((_, first_row)) => {
+                if let Some(columns) = comparer.changed_columns(&first_row, &row) {
+                    changes.push(Change::Changed(name, columns));
+                }
+            }
+            None => changes.push(Change::Added(name, row.to_string())),
+        }
+    }
+
+    let mut removed = unmatched.into_values().flatten().collect::<Vec<_>>();
+    removed.sort_by_key(|(index, _)| *index);
+    for (_, row) in removed {
+        changes.push(Change::Removed(row_key(&row, key), row.to_string()));
+    }
+    Ok(())
+}
+
+/// A file opened for comparison, with its metadata and arrow schema
+struct Compared {
+    file: ParquetFile,
+    metadata: ParquetMetaData,
+    schema: Schema,
+}
+
+fn open_compared(file_name: &Path) -> Result<Compared, PQRSError> {
+    let file = open_file(file_name)?;
+    let metadata = read_metadata(&file, &FooterArgs::default())?;
+    let schema = parquet_to_arrow_schema(
+        metadata.file_metadata().schema_descr(),
+        metadata.file_metadata().key_value_metadata(),
+    )?;
+    Ok(Compared {
+        file,
+        metadata,
+        schema,
+    })
+}
+
+pub(crate) fn execute(opts: DiffCommandArgs) -> Result<(), PQRSError> {
+    debug!(
+        "The files to compare are: {:?}",
+        [&opts.first, &opts.second]
+    );
+    debug!("The key columns are: {:?}", opts.key);
+
+    // make sure all files are present before printing any data
+    for file_name in [&opts.first, &opts.second] {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let first = open_compared(&opts.first).map_err(|e| e.in_file(&opts.first))?;
+    let second = open_compared(&opts.second).map_err(|e| e.in_file(&opts.second))?;
+
+    for column in &opts.key {
+        for compared in [&first, &second] {
+            if compared.schema.field_with_name(column).is_err() {
+                return Err(UnknownKeyColumn(column.to_string()));
+            }
+        }
+    }
+
+    // the parts of the files that differ
+    let mut differences = vec![];
+
+    let mut schema_changes = Changes::new(None);
+    diff_fields(
+        "",
+        first.schema.fields(),
+        second.schema.fields(),
+        &mut schema_changes,
+    );
+    schema_changes.print("Schema");
+    if !schema_changes.is_empty() {
+        differences.push("schema");
+    }
+    let metadata_changes = diff_metadata(&first.metadata, &second.metadata);
+    metadata_changes.print("Key value metadata");
+    if !metadata_changes.is_empty() {
+        differences.push("key value metadata");
+    }
+
+    let first_rows = first.metadata.file_metadata().num_rows();
+    let second_rows = second.metadata.file_metadata().num_rows();
+    if first_rows == second_rows {
+        println!("Row count: identical ({})", first_rows);
+    } else {
+        println!("Row count: {} -> {}", first_rows, second_rows);
+        differences.push("row count");
+    }
+
+    if opts.data || !opts.key.is_empty() {
+        let first_data = read_rows_json(&first.file)
+            .map_err(|e| e.in_file(&opts.first))?
+            .map(|row| row.map_err(|e| e.in_file(&opts.first)));
+        let second_data = read_rows_json(&second.file)
+            .map_err(|e| e.in_file(&opts.second))?
+            .map(|row| row.map_err(|e| e.in_file(&opts.second)));
+        let comparer = RowComparer::new(&first.schema, &second.schema, opts.tolerance);
+        let mut changes = Changes::new(Some(opts.limit));
+        if opts.key.is_empty() {
+            diff_rows_by_position(first_data, second_data, &comparer, &mut changes)?;
+        } else {
+            diff_rows_by_key(
+                first_data,
+                second_data,
+                &opts.key,
+                &comparer,
+                &mut changes,
+            )?;
+        }
+        changes.print("Data");
+        if !changes.is_empty() {
+            differences.push("data");
+        }
+    }
+
+    if !differences.is_empty() {
+        return Err(FilesDiffer(differences.join(", ")));
+    }
+
+    Ok(())
+}
@@ -1,6 +1,10 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, open_file, print_rows, Formats};
+use crate::progress::Progress;
+use crate::render::{RenderArgs, Renderer};
+use crate::utils::{
+    check_path_present, open_file, print_rows, Formats, PrintOptions, Skipped,
+};
 use clap::Parser;
 use log::debug;
 use std::path::PathBuf;
@@ -20,11 +24,20 @@ pub struct HeadCommandArgs {
     #[arg(short = 'n', long, default_value = "5")]
     records: usize,
 
-    /// Parquet file to read
+    /// Skip row groups that cannot be read instead of stopping, and print a summary
+    /// of what was skipped at the end
+    #[arg(long)]
+    skip_corrupt: bool,
+
+    #[command(flatten)]
+    render: RenderArgs,
+
+    /// Parquet file to read, use - to read from stdin
     file: PathBuf,
 }
 
 pub(crate) fn execute(opts: HeadCommandArgs) -> Result<(), PQRSError> {
+    let renderer = Renderer::try_new(&opts.render)?;
     let format = if opts.json {
         Formats::Json
     } else if opts.csv {
@@ -42,7 +55,26 @@ pub(crate) fn execute(opts: HeadCommandArgs) -> Result<(), PQRSError> {
     }
 
     let file = open_file(&opts.file)?;
-    print_rows(file, Some(opts.records), format)?;
+    let mut skipped = Skipped::new(opts.skip_corrupt);
+    let options = PrintOptions {
+        format,
+        render: &renderer,
+        jobs: 1,
+    };
+    print_rows(
+        file.try_clone()?,
+        Some(opts.records),
+        options,
+        &mut Progress::new(false),
+        &mut skipped,
+    )
+    .map_err(|e| e.in_file(&opts.file))?;
+    skipped.print_summary();
+    debug!(
+        "Read {} bytes of {}",
+        file.stats().bytes_read(),
+        opts.file.display()
+    );
 
     Ok(())
 }
@@ -0,0 +1,265 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::FileNotFound;
+use crate::utils::{
+    bloom_filter_size, check_path_present, open_file, print_table, read_footer,
+    read_page_headers, FooterArgs, ParquetFile, SizeFormatArgs,
+};
+use clap::Parser;
+use log::debug;
+use parquet::file::reader::Length;
+use parquet::file::FOOTER_SIZE;
+use parquet::format::PageType;
+use serde::Serialize;
+use std::path::PathBuf;
+
+static PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Prints the byte ranges that make up Parquet file(s), in the order they are stored
+#[derive(Parser, Debug)]
+pub struct LayoutCommandArgs {
+    /// Show each column chunk as a single range instead of its pages, which does not
+    /// need to read the page headers of the column chunks
+    #[arg(long)]
+    chunks: bool,
+
+    /// Print a JSON document with the byte ranges of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The byte ranges of a file, ordered by their offset
+#[derive(Serialize, Debug)]
+struct FileLayout {
+    file: String,
+    file_size: u64,
+    regions: Vec<Region>,
+    /// The number of ranges that are not part of any region, and their bytes
+    gaps: usize,
+    gap_bytes: u64,
+    /// The number of regions that start before the previous ones end
+    overlaps: usize,
+}
+
+/// A range of bytes and what it holds
+#[derive(Serialize, Debug)]
+struct Region {
+    offset: u64,
+    length: u64,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    row_group: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    overlaps: bool,
+}
+
+impl Region {
+    fn new(offset: u64, length: u64, kind: &'static str) -> Region {
+        Region {
+            offset,
+            length,
+            kind,
+            row_group: None,
+            column: None,
+            overlaps: false,
+        }
+    }
+
+    fn in_chunk(mut self, row_group: usize, column: String) -> Region {
+        self.row_group = Some(row_group);
+        self.column = Some(column);
+        self
+    }
+
+    fn end(&self) -> u64 {
+        self.offset + self.length
+    }
+}
+
+impl FileLayout {
+    fn add_gap(&mut self, start: u64, end: u64) {
+        self.regions.push(Region::new(start, end - start, "gap"));
+        self.gaps += 1;
+        self.gap_bytes += end - start;
+    }
+}
+
+pub(crate) fn execute(opts: LayoutCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for (i, file_name) in opts.files.iter().enumerate() {
+        if i > 0 && !opts.json {
+            println!();
+        }
+        let file = open_file(file_name)?;
+        let layout = read_layout(&file, &opts.footer, opts.chunks)
+            .map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&layout)?);
+        } else {
+            print_layout(&layout, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_layout(
+    file: &ParquetFile,
+    footer: &FooterArgs,
+    chunks: bool,
+) -> Result<FileLayout, PQRSError> {
+    let footer = read_footer(file, footer)?;
+    let metadata = &footer.metadata;
+    let file_size = file.len();
+    let metadata_len = footer.metadata_len as u64;
+    let magic_len = PARQUET_MAGIC.len() as u64;
+
+    let mut regions = vec![
+        Region::new(0, magic_len, "magic"),
+        Region::new(
+            file_size - FOOTER_SIZE as u64 - metadata_len,
+            metadata_len,
+            "footer",
+        ),
+        Region::new(file_size - FOOTER_SIZE as u64, 4, "footer_length"),
+        Region::new(file_size - magic_len, magic_len, "magic"),
+    ];
+
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        for column in row_group_metadata.columns() {
+            let path = column.column_path().string();
+            if chunks {
+                let (start, length) = column.byte_range();
+                regions.push(
+                    Region::new(start, length, "column_chunk")
+                        .in_chunk(row_group, path.clone()),
+                );
+            } else {
+                for (offset, header, header_size) in read_page_headers(file, column)? {
+                    let length =
+                        header_size as u64 + header.compressed_page_size.max(0) as u64;
+                    regions.push(
+                        Region::new(offset, length, page_kind(header.type_))
+                            .in_chunk(row_group, path.clone()),
+                    );
+                }
+            }
+
+            if let (Some(offset), Some(length)) =
+                (column.column_index_offset(), column.column_index_length())
+            {
+                regions.push(
+                    Region::new(offset as u64, length as u64, "column_index")
+                        .in_chunk(row_group, path.clone()),
+                );
+            }
+            if let (Some(offset), Some(length)) =
+                (column.offset_index_offset(), column.offset_index_length())
+            {
+                regions.push(
+                    Region::new(offset as u64, length as u64, "offset_index")
+                        .in_chunk(row_group, path.clone()),
+                );
+            }
+            if let Some(offset) = column.bloom_filter_offset() {
+                let length = bloom_filter_size(file, column)?;
+                regions.push(
+                    Region::new(offset as u64, length as u64, "bloom_filter")
+                        .in_chunk(row_group, path.clone()),
+                );
+            }
+        }
+    }
+    regions.sort_by_key(|region| (region.offset, region.length));
+
+    // fill the bytes between the regions with gaps, and flag the regions that start
+    // before the ones before them end
+    let mut layout = FileLayout {
+        file: file.path().display().to_string(),
+        file_size,
+        regions: vec![],
+        gaps: 0,
+        gap_bytes: 0,
+        overlaps: 0,
+    };
+    let mut position = 0;
+    for mut region in regions {
+        if region.offset > position {
+            layout.add_gap(position, region.offset);
+        } else if region.offset < position {
+            region.overlaps = true;
+            layout.overlaps += 1;
+        }
+        position = position.max(region.end());
+        layout.regions.push(region);
+    }
+    if position < file_size {
+        layout.add_gap(position, file_size);
+    }
+
+    Ok(layout)
+}
+
+fn page_kind(page_type: PageType) -> &'static str {
+    match page_type {
+        PageType::DATA_PAGE => "data_page_v1",
+        PageType::DATA_PAGE_V2 => "data_page_v2",
+        PageType::DICTIONARY_PAGE => "dictionary_page",
+        PageType::INDEX_PAGE => "index_page",
+        _ => "unknown_page",
+    }
+}
+
+fn print_layout(layout: &FileLayout, format: SizeFormatArgs) {
+    println!("File Name: {}", layout.file);
+
+    let mut rows = vec![vec![
+        "Offset".to_string(),
+        "Length".to_string(),
+        "Region".to_string(),
+        "Row Group".to_string(),
+        "Column".to_string(),
+    ]];
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    for region in &layout.regions {
+        let kind = if region.overlaps {
+            format!("{} (overlaps)", region.kind)
+        } else {
+            region.kind.to_string()
+        };
+        rows.push(vec![
+            region.offset.to_string(),
+            format.format(region.length as i64),
+            kind,
+            optional(region.row_group.map(|row_group| row_group.to_string())),
+            optional(region.column.clone()),
+        ]);
+    }
+    print_table(&rows, &[true, true, false, true, false]);
+
+    println!("Gaps: {}", layout.gaps);
+    println!("Gap Size: {}", format.format(layout.gap_bytes as i64));
+    if layout.overlaps > 0 {
+        println!("Overlaps: {}", layout.overlaps);
+    }
+}
@@ -1,13 +1,15 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::{FileExists, FileNotFound};
-use crate::utils::{check_path_present, get_row_batches, open_file};
-use parquet::arrow::ArrowWriter;
-use clap::Parser;
+use crate::progress::Progress;
+use crate::utils::{
+    check_path_present, create_output, get_row_batches, is_stdio, open_file,
+};
 use arrow::datatypes::Schema;
+use clap::Parser;
 use log::debug;
-use std::fs::File;
-use std::sync::Arc;
+use parquet::arrow::ArrowWriter;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Merge file(s) into another parquet file
 #[derive(Parser, Debug)]
@@ -16,9 +18,13 @@ pub struct MergeCommandArgs {
     #[arg(short, long, value_delimiter = ' ', num_args = 1..)]
     input: Vec<PathBuf>,
 
-    /// Parquet file to write
+    /// Parquet file to write, use - to write to stdout
     #[arg(short, long)]
     output: PathBuf,
+
+    /// Show the progress of reading the input files on stderr, when it is a terminal
+    #[arg(long)]
+    progress: bool,
 }
 
 pub(crate) fn execute(opts: MergeCommandArgs) -> Result<(), PQRSError> {
@@ -26,7 +32,7 @@ pub(crate) fn execute(opts: MergeCommandArgs) -> Result<(), PQRSError> {
     debug!("The file name to write to: {}", opts.output.display());
 
     // make sure output does not exist already before any reads
-    if check_path_present(&opts.output) {
+    if !is_stdio(&opts.output) && check_path_present(&opts.output) {
         return Err(FileExists(opts.output));
     }
 
@@ -37,40 +43,59 @@ pub(crate) fn execute(opts: MergeCommandArgs) -> Result<(), PQRSError> {
         }
     }
 
+    let mut progress = Progress::new(opts.progress);
+    let mut inputs = vec![];
+    for file_name in &opts.input {
+        let file = open_file(file_name)?;
+        progress.add_file(&file).map_err(|e| e.in_file(file_name))?;
+        inputs.push(file);
+    }
+    let mut inputs = inputs.into_iter();
+
     let mut writer = {
-        let seed = open_file(&opts.input[0])?;
-        let data = get_row_batches(seed)?;
+        let seed = inputs.next().expect("at least one input file");
+        let seed_name = seed.path().to_path_buf();
+        let data =
+            get_row_batches(seed, &mut progress).map_err(|e| e.in_file(&seed_name))?;
 
-        let file = File::create(&opts.output)?;
+        let file = create_output(&opts.output).map_err(|e| e.in_file(&opts.output))?;
         let fields = data.schema.fields().to_vec();
 
         let schema_without_metadata = Schema::new(fields);
 
-        let mut writer = ArrowWriter::try_new(file, Arc::new(schema_without_metadata), None)?;
+        let mut writer =
+            ArrowWriter::try_new(file, Arc::new(schema_without_metadata), None)?;
 
         for record_batch in data.batches.iter() {
-            writer.write(record_batch)?;
+            writer
+                .write(record_batch)
+                .map_err(|e| PQRSError::from(e).in_file(&opts.output))?;
         }
 
         writer
     };
 
-
-    for input in &opts.input[1..] {
-        let current = open_file(input)?;
-        let local = get_row_batches(current)?;
+    for current in inputs {
+        let current_name = current.path().to_path_buf();
+        let local = get_row_batches(current, &mut progress)
+            .map_err(|e| e.in_file(&current_name))?;
 
         // write record batches one at a time
         // record batches are not combined
         for record_batch in local.batches.iter() {
-            writer.write(record_batch)?;
+            writer
+                .write(record_batch)
+                .map_err(|e| PQRSError::from(e).in_file(&opts.output))?;
         }
     }
 
     // closing the writer writes out the FileMetaData
     // if the writer is not closed properly, the metadata footer needed by the parquet
     // format would be corrupt
-    writer.close()?;
+    writer
+        .close()
+        .map_err(|e| PQRSError::from(e).in_file(&opts.output))?;
+    progress.finish();
 
     Ok(())
 }
@@ -1,7 +1,18 @@
+pub(crate) mod bloom;
 pub(crate) mod cat;
+pub(crate) mod dictionary;
+pub(crate) mod diff;
 pub(crate) mod head;
+pub(crate) mod layout;
 pub(crate) mod merge;
+pub(crate) mod page_index;
+pub(crate) mod pages;
+pub(crate) mod recover;
 pub(crate) mod rowcount;
+pub(crate) mod rowgroups;
 pub(crate) mod sample;
 pub(crate) mod schema;
+pub(crate) mod schema_diff;
 pub(crate) mod size;
+pub(crate) mod validate;
+pub(crate) mod verify;
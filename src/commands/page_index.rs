@@ -0,0 +1,309 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{
+    check_path_present, format_plain_value, open_file, print_table, read_metadata,
+    read_thrift, FooterArgs, ParquetFile, SizeFormatArgs,
+};
+use clap::Parser;
+use log::debug;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::ColumnChunkMetaData;
+use parquet::file::reader::ChunkReader;
+use parquet::format::{BoundaryOrder, ColumnIndex, OffsetIndex};
+use parquet::thrift::TSerializable;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Prints the column and offset indexes of Parquet file(s)
+#[derive(Parser, Debug)]
+pub struct PageIndexCommandArgs {
+    /// Only show the indexes of the given columns, as dotted paths, e.g. country.name
+    #[arg(short, long, value_name = "COLUMN")]
+    column: Vec<String>,
+
+    /// Only show the indexes of the given row groups
+    #[arg(short, long, value_name = "ROW_GROUP")]
+    row_group: Vec<usize>,
+
+    /// Only report whether the files have page indexes, without the pages
+    #[arg(short, long)]
+    summary: bool,
+
+    /// Print a JSON document with the page indexes of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The page indexes of the column chunks of a file
+#[derive(Serialize, Debug)]
+struct FilePageIndex {
+    file: String,
+    column_chunks: usize,
+    /// The number of column chunks that have a column index
+    column_indexes: usize,
+    /// The number of column chunks that have an offset index
+    offset_indexes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunks: Option<Vec<ChunkPageIndex>>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChunkPageIndex {
+    row_group: usize,
+    column: String,
+    has_column_index: bool,
+    has_offset_index: bool,
+    boundary_order: Option<&'static str>,
+    pages: Vec<PageEntry>,
+}
+
+/// What the column and offset indexes say about a data page, each of which is left
+/// out when the chunk has no such index
+#[derive(Serialize, Debug, Default)]
+struct PageEntry {
+    offset: Option<i64>,
+    compressed_size: Option<i32>,
+    first_row_index: Option<i64>,
+    /// Whether all the values of the page are null, in which case it has no min or max
+    null_page: Option<bool>,
+    null_count: Option<i64>,
+    min: Option<String>,
+    max: Option<String>,
+}
+
+pub(crate) fn execute(opts: PageIndexCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for (i, file_name) in opts.files.iter().enumerate() {
+        if i > 0 && !opts.json {
+            println!();
+        }
+        let file = open_file(file_name)?;
+        let index =
+            read_file_page_index(&file, &opts).map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&index)?);
+        } else {
+            print_page_index(&index, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_file_page_index(
+    file: &ParquetFile,
+    opts: &PageIndexCommandArgs,
+) -> Result<FilePageIndex, PQRSError> {
+    let metadata = read_metadata(file, &opts.footer)?;
+    let schema = metadata.file_metadata().schema_descr();
+    for column in &opts.column {
+        if !schema
+            .columns()
+            .iter()
+            .any(|c| &c.path().string() == column)
+        {
+            return Err(UnknownColumn(column.to_string()));
+        }
+    }
+
+    let mut index = FilePageIndex {
+        file: file.path().display().to_string(),
+        column_chunks: 0,
+        column_indexes: 0,
+        offset_indexes: 0,
+        chunks: None,
+    };
+    let mut chunks = vec![];
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        for column in row_group_metadata.columns() {
+            index.column_chunks += 1;
+            if column.column_index_offset().is_some() {
+                index.column_indexes += 1;
+            }
+            if column.offset_index_offset().is_some() {
+                index.offset_indexes += 1;
+            }
+
+            let path = column.column_path().string();
+            let selected = (opts.row_group.is_empty()
+                || opts.row_group.contains(&row_group))
+                && (opts.column.is_empty() || opts.column.contains(&path));
+            if selected && !opts.summary {
+                chunks.push(read_chunk_page_index(file, column, row_group)?);
+            }
+        }
+    }
+    if !opts.summary {
+        index.chunks = Some(chunks);
+    }
+
+    Ok(index)
+}
+
+/// Read the thrift struct of an index at the given offset and length, if there is one
+fn read_index<T: TSerializable>(
+    file: &ParquetFile,
+    offset: Option<i64>,
+    length: Option<i32>,
+    column: &ColumnChunkMetaData,
+) -> Result<Option<T>, PQRSError> {
+    let (offset, length) = match (offset, length) {
+        (Some(offset), Some(length)) if offset >= 0 && length >= 0 => (offset, length),
+        _ => return Ok(None),
+    };
+    let bytes = file.get_bytes(offset as u64, length as usize)?;
+    let (index, _) = read_thrift(&bytes).map_err(|e| {
+        ParquetError::General(format!(
+            "Could not decode the page index at offset {} of column {}: {}",
+            offset,
+            column.column_path().string(),
+            e
+        ))
+    })?;
+    Ok(Some(index))
+}
+
+fn read_chunk_page_index(
+    file: &ParquetFile,
+    column: &ColumnChunkMetaData,
+    row_group: usize,
+) -> Result<ChunkPageIndex, PQRSError> {
+    let column_index: Option<ColumnIndex> = read_index(
+        file,
+        column.column_index_offset(),
+        column.column_index_length(),
+        column,
+    )?;
+    let offset_index: Option<OffsetIndex> = read_index(
+        file,
+        column.offset_index_offset(),
+        column.offset_index_length(),
+        column,
+    )?;
+
+    let num_pages = match (&column_index, &offset_index) {
+        (_, Some(offset_index)) => offset_index.page_locations.len(),
+        (Some(column_index), None) => column_index.null_pages.len(),
+        (None, None) => 0,
+    };
+    let mut pages = (0..num_pages)
+        .map(|_| PageEntry::default())
+        .collect::<Vec<_>>();
+
+    if let Some(offset_index) = &offset_index {
+        for (page, location) in pages.iter_mut().zip(&offset_index.page_locations) {
+            page.offset = Some(location.offset);
+            page.compressed_size = Some(location.compressed_page_size);
+            page.first_row_index = Some(location.first_row_index);
+        }
+    }
+    if let Some(column_index) = &column_index {
+        let physical_type = column.column_type();
+        for (i, page) in pages.iter_mut().enumerate() {
+            let null_page = column_index.null_pages.get(i).copied().unwrap_or(false);
+            page.null_page = Some(null_page);
+            page.null_count = column_index
+                .null_counts
+                .as_ref()
+                .and_then(|counts| counts.get(i).copied());
+            if !null_page {
+                page.min = column_index
+                    .min_values
+                    .get(i)
+                    .map(|value| format_plain_value(physical_type, value));
+                page.max = column_index
+                    .max_values
+                    .get(i)
+                    .map(|value| format_plain_value(physical_type, value));
+            }
+        }
+    }
+
+    Ok(ChunkPageIndex {
+        row_group,
+        column: column.column_path().string(),
+        has_column_index: column_index.is_some(),
+        has_offset_index: offset_index.is_some(),
+        boundary_order: column_index
+            .as_ref()
+            .map(|index| boundary_order_name(index.boundary_order)),
+        pages,
+    })
+}
+
+fn boundary_order_name(order: BoundaryOrder) -> &'static str {
+    match order {
+        BoundaryOrder::ASCENDING => "ascending",
+        BoundaryOrder::DESCENDING => "descending",
+        _ => "unordered",
+    }
+}
+
+fn print_page_index(index: &FilePageIndex, format: SizeFormatArgs) {
+    println!("File Name: {}", index.file);
+    println!(
+        "Column Index: {} of {} column chunks",
+        index.column_indexes, index.column_chunks
+    );
+    println!(
+        "Offset Index: {} of {} column chunks",
+        index.offset_indexes, index.column_chunks
+    );
+
+    for chunk in index.chunks.iter().flatten() {
+        println!();
+        match chunk.boundary_order {
+            Some(order) => println!(
+                "Row Group {}, Column {} ({}):",
+                chunk.row_group, chunk.column, order
+            ),
+            None => println!("Row Group {}, Column {}:", chunk.row_group, chunk.column),
+        }
+        if chunk.pages.is_empty() {
+            println!("No page index");
+            continue;
+        }
+
+        let mut rows = vec![vec![
+            "Page".to_string(),
+            "Offset".to_string(),
+            "Compressed".to_string(),
+            "First Row".to_string(),
+            "Nulls".to_string(),
+            "Min".to_string(),
+            "Max".to_string(),
+        ]];
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        for (i, page) in chunk.pages.iter().enumerate() {
+            rows.push(vec![
+                i.to_string(),
+                optional(page.offset.map(|offset| offset.to_string())),
+                optional(page.compressed_size.map(|size| format.format(size as i64))),
+                optional(page.first_row_index.map(|row| row.to_string())),
+                optional(page.null_count.map(|count| count.to_string())),
+                optional(page.min.clone()),
+                optional(page.max.clone()),
+            ]);
+        }
+        print_table(&rows, &[true, true, true, true, true, false, false]);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{
+    check_path_present, encoding_name, format_min_max, open_file, print_table,
+    read_metadata, read_page_headers, FooterArgs, ParquetFile, SizeFormatArgs,
+};
+use clap::Parser;
+use log::debug;
+use parquet::file::metadata::ColumnChunkMetaData;
+use parquet::file::statistics::from_thrift;
+use parquet::format::{PageHeader, PageType};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Prints the pages of the column chunks in Parquet file(s)
+#[derive(Parser, Debug)]
+pub struct PagesCommandArgs {
+    /// Only show the pages of the given columns, as dotted paths, e.g. country.name
+    #[arg(short, long, value_name = "COLUMN")]
+    column: Vec<String>,
+
+    /// Only show the pages of the given row groups
+    #[arg(short, long, value_name = "ROW_GROUP")]
+    row_group: Vec<usize>,
+
+    /// Print a JSON document with the pages of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The pages of the column chunks of a file
+#[derive(Serialize, Debug)]
+struct FilePages {
+    file: String,
+    chunks: Vec<ChunkPages>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChunkPages {
+    row_group: usize,
+    column: String,
+    pages: Vec<Page>,
+}
+
+/// What the header of a page says about it
+#[derive(Serialize, Debug)]
+struct Page {
+    offset: u64,
+    page_type: &'static str,
+    encoding: Option<String>,
+    header_size: usize,
+    compressed_size: i32,
+    uncompressed_size: i32,
+    /// The number of values including nulls, not set for index pages
+    num_values: Option<i32>,
+    /// Only data pages v2 have the number of nulls and rows in their header, for data
+    /// pages v1 the null count is taken from the statistics
+    num_nulls: Option<i64>,
+    num_rows: Option<i32>,
+    statistics: Option<PageStatistics>,
+}
+
+/// The statistics in a page header, with the values as they are stored
+#[derive(Serialize, Debug)]
+struct PageStatistics {
+    min: Option<String>,
+    max: Option<String>,
+    null_count: Option<i64>,
+    distinct_count: Option<i64>,
+}
+
+pub(crate) fn execute(opts: PagesCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for file_name in &opts.files {
+        let file = open_file(file_name)?;
+        let pages = read_file_pages(&file, &opts.footer, &opts.column, &opts.row_group)
+            .map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&pages)?);
+        } else {
+            print_pages(&pages, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_file_pages(
+    file: &ParquetFile,
+    footer: &FooterArgs,
+    columns: &[String],
+    row_groups: &[usize],
+) -> Result<FilePages, PQRSError> {
+    let metadata = read_metadata(file, footer)?;
+    let schema = metadata.file_metadata().schema_descr();
+    for column in columns {
+        if !schema
+            .columns()
+            .iter()
+            .any(|c| &c.path().string() == column)
+        {
+            return Err(UnknownColumn(column.to_string()));
+        }
+    }
+
+    let mut chunks = vec![];
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        if !row_groups.is_empty() && !row_groups.contains(&row_group) {
+            continue;
+        }
+        for column in row_group_metadata.columns() {
+            let path = column.column_path().string();
+            if !columns.is_empty() && !columns.contains(&path) {
+                continue;
+            }
+            chunks.push(ChunkPages {
+                row_group,
+                column: path,
+                pages: read_page_headers(file, column)?
+                    .iter()
+                    .map(|(offset, header, header_size)| {
+                        describe_page(column, *offset, header, *header_size)
+                    })
+                    .collect::<Result<_, _>>()?,
+            });
+        }
+    }
+
+    Ok(FilePages {
+        file: file.path().display().to_string(),
+        chunks,
+    })
+}
+
+fn describe_page(
+    column: &ColumnChunkMetaData,
+    offset: u64,
+    header: &PageHeader,
+    header_size: usize,
+) -> Result<Page, PQRSError> {
+    let mut page = Page {
+        offset,
+        page_type: page_type_name(header.type_),
+        encoding: None,
+        header_size,
+        compressed_size: header.compressed_page_size,
+        uncompressed_size: header.uncompressed_page_size,
+        num_values: None,
+        num_nulls: None,
+        num_rows: None,
+        statistics: None,
+    };
+
+    let statistics = if let Some(dictionary) = &header.dictionary_page_header {
+        page.encoding = Some(encoding_name(dictionary.encoding));
+        page.num_values = Some(dictionary.num_values);
+        None
+    } else if let Some(data) = &header.data_page_header {
+        page.encoding = Some(encoding_name(data.encoding));
+        page.num_values = Some(data.num_values);
+        page.num_nulls = data.statistics.as_ref().and_then(|s| s.null_count);
+        data.statistics.clone()
+    } else if let Some(data) = &header.data_page_header_v2 {
+        page.encoding = Some(encoding_name(data.encoding));
+        page.num_values = Some(data.num_values);
+        page.num_nulls = Some(data.num_nulls as i64);
+        page.num_rows = Some(data.num_rows);
+        data.statistics.clone()
+    } else {
+        None
+    };
+
+    if let Some(statistics) = statistics {
+        let null_count = statistics.null_count;
+        let distinct_count = statistics.distinct_count;
+        let typed = from_thrift(column.column_type(), Some(statistics))?;
+        let min_max = typed.as_ref().and_then(format_min_max);
+        page.statistics = Some(PageStatistics {
+            min: min_max.as_ref().map(|(min, _)| min.to_string()),
+            max: min_max.map(|(_, max)| max),
+            null_count,
+            distinct_count,
+        });
+    }
+
+    Ok(page)
+}
+
+fn page_type_name(page_type: PageType) -> &'static str {
+    match page_type {
+        PageType::DATA_PAGE => "data_v1",
+        PageType::DATA_PAGE_V2 => "data_v2",
+        PageType::DICTIONARY_PAGE => "dictionary",
+        PageType::INDEX_PAGE => "index",
+        _ => "unknown",
+    }
+}
+
+fn print_pages(pages: &FilePages, format: SizeFormatArgs) {
+    println!("File Name: {}", pages.file);
+    for chunk in &pages.chunks {
+        println!();
+        println!("Row Group {}, Column {}:", chunk.row_group, chunk.column);
+
+        let mut rows = vec![vec![
+            "Offset".to_string(),
+            "Type".to_string(),
+            "Encoding".to_string(),
+            "Header".to_string(),
+            "Compressed".to_string(),
+            "Uncompressed".to_string(),
+            "Values".to_string(),
+            "Nulls".to_string(),
+            "Min".to_string(),
+            "Max".to_string(),
+        ]];
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        for page in &chunk.pages {
+            let statistics = page.statistics.as_ref();
+            rows.push(vec![
+                page.offset.to_string(),
+                page.page_type.to_string(),
+                optional(page.encoding.clone()),
+                format.format(page.header_size as i64),
+                format.format(page.compressed_size as i64),
+                format.format(page.uncompressed_size as i64),
+                optional(page.num_values.map(|n| n.to_string())),
+                optional(page.num_nulls.map(|n| n.to_string())),
+                optional(statistics.and_then(|s| s.min.clone())),
+                optional(statistics.and_then(|s| s.max.clone())),
+            ]);
+        }
+        print_table(
+            &rows,
+            &[
+                true, false, false, true, true, true, true, true, false, false,
+            ],
+        );
+    }
+}
@@ -0,0 +1,901 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{
+    FileExists, FileNotFound, InvalidSchema, NothingRecovered,
+};
+use crate::schema_formats::SchemaNode;
+use crate::utils::{
+    check_path_present, create_output, is_stdio, open_file, read_metadata,
+    read_page_header, read_thrift, FooterArgs, ParquetFile, PAGE_HEADER_READ_SIZE,
+};
+use arrow::datatypes::Schema;
+use bytes::Bytes;
+use clap::Parser;
+use log::debug;
+use parquet::arrow::arrow_to_parquet_schema;
+use parquet::basic::Type as PhysicalType;
+use parquet::basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel};
+use parquet::column::reader::{get_column_reader, ColumnReader, ColumnReaderImpl};
+use parquet::column::writer::ColumnCloseResult;
+use parquet::compression::{create_codec, CodecOptions};
+use parquet::data_type::DataType;
+use parquet::errors::ParquetError;
+use parquet::file::metadata::{ColumnChunkMetaData, KeyValue};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::file::serialized_reader::SerializedPageReader;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::format::{ColumnChunk, ColumnMetaData, PageHeader, PageType, Statistics};
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::{ColumnPath, SchemaDescPtr, SchemaDescriptor};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::cmp::min;
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once};
+
+static PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// The number of records decoded at a time when counting the rows of a page
+static BATCH_SIZE: usize = 8192;
+
+/// The most bytes read for a page header or column metadata while scanning the file.
+/// Bytes that do not decode as either within this size end the scan.
+static MAX_HEADER_READ_SIZE: usize = 1024 * 1024;
+
+static SILENCE_PANICS: Once = Once::new();
+
+thread_local! {
+    /// Whether panics on this thread are caught by catch_panic and not printed
+    static PANICS_SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Recovers the row groups of a Parquet file whose footer is missing or broken
+#[derive(Parser, Debug)]
+pub struct RecoverCommandArgs {
+    /// The schema the file was written with: a Parquet file with the same schema, the
+    /// output of schema --json or schema --arrow, or a Parquet message type
+    #[arg(short, long)]
+    schema: PathBuf,
+
+    /// Parquet file to write the recovered rows to, use - to write to stdout
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Damaged Parquet file to recover, use - to read from stdin
+    file: PathBuf,
+}
+
+/// The compressions a column chunk may have been written with, in the order they are
+/// tried. The page headers do not say how their pages were compressed.
+fn compressions() -> [Compression; 7] {
+    [
+        Compression::SNAPPY,
+        Compression::ZSTD(ZstdLevel::default()),
+        Compression::GZIP(GzipLevel::default()),
+        Compression::LZ4_RAW,
+        Compression::LZ4,
+        Compression::BROTLI(BrotliLevel::default()),
+        Compression::UNCOMPRESSED,
+    ]
+}
+
+/// A page found by scanning the file
+struct ScannedPage {
+    offset: usize,
+    header: PageHeader,
+    header_len: usize,
+    /// Whether the page is followed by a copy of the metadata of its column chunk,
+    /// which some writers put after each column chunk
+    ends_chunk: bool,
+}
+
+impl ScannedPage {
+    fn range(&self) -> Range<usize> {
+        self.offset
+            ..self.offset + self.header_len + self.header.compressed_page_size as usize
+    }
+
+    fn body(&self) -> Range<usize> {
+        self.offset + self.header_len..self.range().end
+    }
+
+    fn is_dictionary(&self) -> bool {
+        self.header.type_ == PageType::DICTIONARY_PAGE
+    }
+}
+
+/// A column chunk made up of consecutive pages, the first of which may be its dictionary
+struct Chunk {
+    pages: Range<usize>,
+    compression: Compression,
+}
+
+struct RecoveredRowGroup {
+    num_rows: usize,
+    chunks: Vec<Chunk>,
+}
+
+/// Splits the pages found in the file into the column chunks of row groups.
+/// Pages do not say which column they belong to, so chunks are told apart by their
+/// dictionary pages, the column metadata that follows them, whether their pages can be
+/// decoded as values of the column and the number of rows they hold, which is the same
+/// for every column chunk of a row group.
+struct Assembler<'a> {
+    file: &'a ParquetFile,
+    schema: &'a SchemaDescriptor,
+    pages: &'a [ScannedPage],
+    /// The number of rows in a page by column, page, dictionary page and compression,
+    /// or None if the page cannot be decoded that way
+    rows: HashMap<(usize, usize, Option<usize>, usize), Option<usize>>,
+}
+
+impl<'a> Assembler<'a> {
+    fn new(
+        file: &'a ParquetFile,
+        schema: &'a SchemaDescriptor,
+        pages: &'a [ScannedPage],
+    ) -> Self {
+        Assembler {
+            file,
+            schema,
+            pages,
+            rows: HashMap::new(),
+        }
+    }
+
+    /// Read the given bytes of the file
+    fn read(&self, range: Range<usize>) -> Result<Bytes, ParquetError> {
+        self.file.get_bytes(range.start as u64, range.len())
+    }
+
+    /// Find the row group made up of the pages from `start` on
+    fn row_group(&mut self, start: usize) -> Option<RecoveredRowGroup> {
+        let (dictionary, first) = self.chunk_start(start)?;
+        let compression = self.detect_compression(0, start)?;
+
+        // the first column chunk may end after any of its data pages, which sets the
+        // number of rows of the row group. The largest row group that fits is kept.
+        let mut ends = vec![];
+        let mut num_rows = 0;
+        for page in first..self.pages.len() {
+            if self.pages[page].is_dictionary() {
+                break;
+            }
+            match self.page_rows(0, dictionary, page, compression) {
+                Some(rows) => num_rows += rows,
+                None => break,
+            }
+            ends.push((page + 1, num_rows));
+            if self.pages[page].ends_chunk {
+                break;
+            }
+        }
+
+        for (end, num_rows) in ends.into_iter().rev() {
+            let mut chunks = vec![Chunk {
+                pages: start..end,
+                compression: compressions()[compression],
+            }];
+            for column in 1..self.schema.num_columns() {
+                let next = chunks.last().expect("the first chunk is added").pages.end;
+                match self.chunk(column, next, num_rows) {
+                    Some(chunk) => chunks.push(chunk),
+                    None => break,
+                }
+            }
+            if chunks.len() == self.schema.num_columns() {
+                return Some(RecoveredRowGroup { num_rows, chunks });
+            }
+        }
+        None
+    }
+
+    /// Find the chunk of the column with the given number of rows starting at `start`
+    fn chunk(&mut self, column: usize, start: usize, num_rows: usize) -> Option<Chunk> {
+        let (dictionary, first) = self.chunk_start(start)?;
+        let compression = self.detect_compression(column, start)?;
+
+        let mut rows = 0;
+        let mut end = first;
+        while rows < num_rows {
+            let is_dictionary = self.pages.get(end)?.is_dictionary();
+            if is_dictionary || (end > first && self.pages[end - 1].ends_chunk) {
+                return None;
+            }
+            rows += self.page_rows(column, dictionary, end, compression)?;
+            end += 1;
+        }
+
+        (rows == num_rows).then(|| Chunk {
+            pages: start..end,
+            compression: compressions()[compression],
+        })
+    }
+
+    /// The dictionary page and the first data page of a chunk starting at `start`
+    fn chunk_start(&self, start: usize) -> Option<(Option<usize>, usize)> {
+        let page = self.pages.get(start)?;
+        if page.is_dictionary() {
+            Some((Some(start), start + 1))
+        } else {
+            Some((None, start))
+        }
+    }
+
+    /// The compression of the chunk of the column starting at `start`, found by
+    /// decoding its first data page with each of the compressions in turn
+    fn detect_compression(&mut self, column: usize, start: usize) -> Option<usize> {
+        let (dictionary, first) = self.chunk_start(start)?;
+        if first >= self.pages.len() {
+            return None;
+        }
+        (0..compressions().len()).find(|&compression| {
+            self.page_rows(column, dictionary, first, compression)
+                .is_some()
+        })
+    }
+
+    fn page_rows(
+        &mut self,
+        column: usize,
+        dictionary: Option<usize>,
+        page: usize,
+        compression: usize,
+    ) -> Option<usize> {
+        let key = (column, page, dictionary, compression);
+        if let Some(rows) = self.rows.get(&key) {
+            return *rows;
+        }
+        let rows = self
+            .check_page(column, dictionary, page, compression)
+            .ok()
+            .and_then(|_| {
+                catch_panic(|| self.count_rows(column, dictionary, page, compression))
+            })
+            .and_then(|rows| rows.ok());
+        self.rows.insert(key, rows);
+        rows
+    }
+
+    /// Check the parts of the page that the decoders of the parquet crate panic on
+    /// instead of returning an error, which are expected when a page is decoded as
+    /// part of the wrong column or with the wrong compression: the lengths of its
+    /// levels, its statistics, its dictionary and its dictionary indexes
+    fn check_page(
+        &self,
+        column: usize,
+        dictionary: Option<usize>,
+        page: usize,
+        compression: usize,
+    ) -> Result<(), ParquetError> {
+        let descr = self.schema.column(column);
+        let page = &self.pages[page];
+        let body = &self.read(page.body())?[..];
+        let compression = compressions()[compression];
+        let uncompressed_size = page.header.uncompressed_page_size as usize;
+        let invalid = |message: &str| Err(ParquetError::General(message.to_string()));
+
+        // a data page v1 is compressed as a whole, while a data page v2 stores its
+        // levels uncompressed before its values
+        let (encoding, num_values, statistics, values) =
+            if let Some(header) = &page.header.data_page_header {
+                let data = decompress(body, uncompressed_size, compression)?;
+                let mut values = &data[..];
+                for (max_level, encoding) in [
+                    (descr.max_rep_level(), header.repetition_level_encoding),
+                    (descr.max_def_level(), header.definition_level_encoding),
+                ] {
+                    if max_level > 0 {
+                        match levels_len(max_level, header.num_values, encoding, values) {
+                            Some(len) => values = &values[len..],
+                            None => return invalid("Invalid levels"),
+                        }
+                    }
+                }
+                let values = values.to_vec();
+                (
+                    header.encoding,
+                    header.num_values,
+                    &header.statistics,
+                    values,
+                )
+            } else if let Some(header) = &page.header.data_page_header_v2 {
+                let rep_len = usize::try_from(header.repetition_levels_byte_length);
+                let def_len = usize::try_from(header.definition_levels_byte_length);
+                let levels_len = match (rep_len, def_len) {
+                    (Ok(rep_len), Ok(def_len)) if rep_len + def_len <= body.len() => {
+                        rep_len + def_len
+                    }
+                    _ => return invalid("Invalid levels"),
+                };
+                let values = if header.is_compressed.unwrap_or(true) {
+                    let size = uncompressed_size.saturating_sub(levels_len);
+                    decompress(&body[levels_len..], size, compression)?
+                } else {
+                    body[levels_len..].to_vec()
+                };
+                (
+                    header.encoding,
+                    header.num_values,
+                    &header.statistics,
+                    values,
+                )
+            } else {
+                return Ok(());
+            };
+
+        if !statistics_fit(descr.physical_type(), statistics.as_ref()) {
+            return invalid("Invalid statistics");
+        }
+
+        let dictionary_encoded = encoding == parquet::format::Encoding::PLAIN_DICTIONARY
+            || encoding == parquet::format::Encoding::RLE_DICTIONARY;
+        match dictionary.map(|dictionary| &self.pages[dictionary]) {
+            Some(dictionary) => {
+                let dictionary_len = dictionary
+                    .header
+                    .dictionary_page_header
+                    .as_ref()
+                    .map_or(0, |header| header.num_values);
+                if descr.physical_type() == PhysicalType::BYTE_ARRAY {
+                    let size = dictionary.header.uncompressed_page_size as usize;
+                    let buf =
+                        decompress(&self.read(dictionary.body())?, size, compression)?;
+                    if !byte_arrays_fit(&buf, dictionary_len) {
+                        return invalid("Invalid dictionary");
+                    }
+                }
+                if dictionary_encoded
+                    && !dictionary_indexes_fit(&values, num_values, dictionary_len)
+                {
+                    return invalid("Invalid dictionary indexes");
+                }
+            }
+            None if dictionary_encoded => {
+                return invalid("Dictionary encoded page without a dictionary");
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Decode the page as part of a chunk of the column and count the rows it holds
+    fn count_rows(
+        &self,
+        column: usize,
+        dictionary: Option<usize>,
+        page: usize,
+        compression: usize,
+    ) -> Result<usize, ParquetError> {
+        let mut buf = vec![];
+        if let Some(dictionary) = dictionary {
+            buf.extend_from_slice(&self.read(self.pages[dictionary].range())?);
+        }
+        let data_page_offset = buf.len();
+        buf.extend_from_slice(&self.read(self.pages[page].range())?);
+
+        let descr = self.schema.column(column);
+        let metadata = ColumnChunkMetaData::builder(descr.clone())
+            .set_compression(compressions()[compression])
+            .set_dictionary_page_offset(dictionary.map(|_| 0))
+            .set_data_page_offset(data_page_offset as i64)
+            .set_total_compressed_size(buf.len() as i64)
+            .build()?;
+        let page_reader =
+            SerializedPageReader::new(Arc::new(Bytes::from(buf)), &metadata, 0, None)?;
+
+        let repeated = descr.max_rep_level() > 0;
+        match get_column_reader(descr, Box::new(page_reader)) {
+            ColumnReader::BoolColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::Int32ColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::Int64ColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::Int96ColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::FloatColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::DoubleColumnReader(reader) => count_records(reader, repeated),
+            ColumnReader::ByteArrayColumnReader(reader) => {
+                count_records(reader, repeated)
+            }
+            ColumnReader::FixedLenByteArrayColumnReader(reader) => {
+                count_records(reader, repeated)
+            }
+        }
+    }
+
+    /// The metadata of the chunk for writing it to another file
+    fn close_result(
+        &self,
+        column: usize,
+        chunk: &Chunk,
+        num_rows: usize,
+    ) -> Result<ColumnCloseResult, ParquetError> {
+        let pages = &self.pages[chunk.pages.clone()];
+        let mut encodings = vec![];
+        let mut num_values = 0;
+        let mut uncompressed_size = 0;
+        for page in pages {
+            let header = &page.header;
+            uncompressed_size +=
+                page.header_len as i64 + header.uncompressed_page_size as i64;
+            let page_encodings = if let Some(header) = &header.data_page_header {
+                num_values += header.num_values as i64;
+                vec![
+                    header.encoding,
+                    header.definition_level_encoding,
+                    header.repetition_level_encoding,
+                ]
+            } else if let Some(header) = &header.data_page_header_v2 {
+                num_values += header.num_values as i64;
+                vec![header.encoding, parquet::format::Encoding::RLE]
+            } else if let Some(header) = &header.dictionary_page_header {
+                vec![header.encoding]
+            } else {
+                vec![]
+            };
+            for encoding in page_encodings {
+                let encoding = Encoding::try_from(encoding)?;
+                if !encodings.contains(&encoding) {
+                    encodings.push(encoding);
+                }
+            }
+        }
+
+        let (first, last) = match (pages.first(), pages.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                return Err(ParquetError::General(
+                    "Column chunk without pages".to_string(),
+                ))
+            }
+        };
+        let (dictionary_page_offset, data_page_offset) = if first.is_dictionary() {
+            let data_page = pages.get(1).ok_or_else(|| {
+                ParquetError::General("Column chunk without data pages".to_string())
+            })?;
+            (Some(first.offset as i64), data_page.offset as i64)
+        } else {
+            (None, first.offset as i64)
+        };
+        let start = first.offset;
+        let end = last.range().end;
+
+        let metadata = ColumnChunkMetaData::builder(self.schema.column(column))
+            .set_compression(chunk.compression)
+            .set_encodings(encodings)
+            .set_num_values(num_values)
+            .set_total_compressed_size((end - start) as i64)
+            .set_total_uncompressed_size(uncompressed_size)
+            .set_dictionary_page_offset(dictionary_page_offset)
+            .set_data_page_offset(data_page_offset)
+            .build()?;
+
+        Ok(ColumnCloseResult {
+            bytes_written: (end - start) as u64,
+            rows_written: num_rows as u64,
+            metadata,
+            bloom_filter: None,
+            column_index: None,
+            offset_index: None,
+        })
+    }
+}
+
+/// Run the function, returning None if it panics. The pages are checked for what the
+/// decoders of the parquet crate are known to panic on before they are decoded, so
+/// this only guards against malformed pages that are not checked for. The panic is
+/// expected, so it is not printed.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Option<T> {
+    SILENCE_PANICS.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !PANICS_SILENCED.with(Cell::get) {
+                hook(info);
+            }
+        }));
+    });
+    PANICS_SILENCED.with(|silenced| silenced.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).ok();
+    PANICS_SILENCED.with(|silenced| silenced.set(false));
+    result
+}
+
+/// The number of bytes taken up by the levels of a data page v1 at the start of the
+/// buffer, or None if they do not fit in it
+fn levels_len(
+    max_level: i16,
+    num_values: i32,
+    encoding: parquet::format::Encoding,
+    buf: &[u8],
+) -> Option<usize> {
+    let len = match encoding {
+        parquet::format::Encoding::RLE => {
+            let prefix = buf.get(..4)?.try_into().ok()?;
+            usize::try_from(i32::from_le_bytes(prefix))
+                .ok()?
+                .checked_add(4)?
+        }
+        parquet::format::Encoding::BIT_PACKED => {
+            let bit_width = 16 - max_level.leading_zeros() as usize;
+            let bits = usize::try_from(num_values).ok()?.checked_mul(bit_width)?;
+            (bits + 7) / 8
+        }
+        _ => return None,
+    };
+    (len <= buf.len()).then_some(len)
+}
+
+/// Decompress the buffer with the given compression
+fn decompress(
+    buf: &[u8],
+    uncompressed_size: usize,
+    compression: Compression,
+) -> Result<Vec<u8>, ParquetError> {
+    match create_codec(compression, &CodecOptions::default())? {
+        Some(mut codec) => {
+            let mut decompressed = vec![];
+            codec.decompress(buf, &mut decompressed, Some(uncompressed_size))?;
+            Ok(decompressed)
+        }
+        None => Ok(buf.to_vec()),
+    }
+}
+
+/// Whether the min and max values of the statistics of a page have the size of the
+/// physical type of its column
+fn statistics_fit(physical_type: PhysicalType, statistics: Option<&Statistics>) -> bool {
+    let size = match physical_type {
+        PhysicalType::BOOLEAN => 1,
+        PhysicalType::INT32 | PhysicalType::FLOAT => 4,
+        PhysicalType::INT64 | PhysicalType::DOUBLE => 8,
+        PhysicalType::INT96 => 12,
+        _ => return true,
+    };
+    statistics.map_or(true, |statistics| {
+        [
+            &statistics.min,
+            &statistics.max,
+            &statistics.min_value,
+            &statistics.max_value,
+        ]
+        .iter()
+        .all(|value| value.as_ref().map_or(true, |value| value.len() == size))
+    })
+}
+
+/// Whether the buffer holds the given number of PLAIN encoded byte arrays
+fn byte_arrays_fit(buf: &[u8], num_values: i32) -> bool {
+    let mut position = 0;
+    for _ in 0..num_values {
+        match buf.get(position..position + 4) {
+            Some(&[a, b, c, d]) => {
+                position += 4 + u32::from_le_bytes([a, b, c, d]) as usize
+            }
+            _ => return false,
+        }
+    }
+    position <= buf.len()
+}
+
+/// Whether the first values of the RLE encoded dictionary indexes at the start of the
+/// buffer, which the given number of values of a page are decoded from, are all in
+/// the dictionary
+fn dictionary_indexes_fit(buf: &[u8], num_values: i32, dictionary_len: i32) -> bool {
+    let (bit_width, mut buf) = match buf.split_first() {
+        Some((bit_width, buf)) if *bit_width <= 32 => (*bit_width as usize, buf),
+        Some(_) => return false,
+        None => return true,
+    };
+    let in_dictionary = |index: u64| index < dictionary_len.max(0) as u64;
+    let mut remaining = num_values.max(0) as u64;
+
+    while remaining > 0 && !buf.is_empty() {
+        let (header, header_len) = match read_uleb128(buf) {
+            Some(header) => header,
+            None => return false,
+        };
+        buf = &buf[header_len..];
+        if header & 1 == 1 {
+            // groups of 8 bit packed indexes
+            let len = (header >> 1).saturating_mul(bit_width as u64);
+            let len = len.min(buf.len() as u64) as usize;
+            let count = ((len * 8) / bit_width.max(1)) as u64;
+            for i in 0..count.min(remaining) as usize {
+                let index = (0..bit_width).fold(0, |index, bit| {
+                    let position = i * bit_width + bit;
+                    index | (((buf[position / 8] >> (position % 8)) & 1) as u64) << bit
+                });
+                if !in_dictionary(index) {
+                    return false;
+                }
+            }
+            remaining = remaining.saturating_sub((header >> 1).saturating_mul(8));
+            buf = &buf[len..];
+        } else {
+            // a run of the same index
+            let len = ((bit_width + 7) / 8).min(buf.len());
+            let index = buf[..len]
+                .iter()
+                .rev()
+                .fold(0, |index, byte| index << 8 | *byte as u64);
+            if header >> 1 > 0 && !in_dictionary(index) {
+                return false;
+            }
+            remaining = remaining.saturating_sub(header >> 1);
+            buf = &buf[len..];
+        }
+    }
+    true
+}
+
+/// Decode the unsigned LEB128 number at the start of the buffer, returning it and the
+/// number of bytes it takes up
+fn read_uleb128(buf: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0;
+    for (i, byte) in buf.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Decode all the records of the column and return how many start in it. The records
+/// of a repeated column may span pages, so only the values with a repetition level of
+/// zero, which start a record, are counted.
+fn count_records<T: DataType>(
+    mut reader: ColumnReaderImpl<T>,
+    repeated: bool,
+) -> Result<usize, ParquetError> {
+    let mut def_levels = vec![];
+    let mut rep_levels = vec![];
+    let mut values = vec![];
+    let mut total = 0;
+    loop {
+        def_levels.clear();
+        rep_levels.clear();
+        values.clear();
+        let (records, _, _) = reader.read_records(
+            BATCH_SIZE,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+        if records == 0 {
+            return Ok(total);
+        }
+        total += if repeated {
+            rep_levels.iter().filter(|level| **level == 0).count()
+        } else {
+            records
+        };
+    }
+}
+
+/// Scan the file for consecutive pages from its start, stopping at the first bytes
+/// that are not a complete page, such as the footer or where the file was cut off.
+/// Only the headers of the pages are read.
+fn scan_pages(
+    file: &ParquetFile,
+    schema: &SchemaDescriptor,
+) -> Result<Vec<ScannedPage>, ParquetError> {
+    let len = file.len() as usize;
+    let mut pages: Vec<ScannedPage> = vec![];
+    let mut position = if len >= PARQUET_MAGIC.len()
+        && file.get_bytes(0, PARQUET_MAGIC.len())? == PARQUET_MAGIC
+    {
+        PARQUET_MAGIC.len()
+    } else {
+        0
+    };
+
+    loop {
+        let remaining = len - position;
+        if let Some((header, header_len)) =
+            read_at(file, position, |buf| read_page(buf, remaining))?
+        {
+            let page = ScannedPage {
+                offset: position,
+                header,
+                header_len,
+                ends_chunk: false,
+            };
+            position = page.range().end;
+            pages.push(page);
+        } else if let Some(len) =
+            read_at(file, position, |buf| read_column_metadata(buf, schema))?
+        {
+            match pages.last_mut() {
+                Some(page) => page.ends_chunk = true,
+                None => break,
+            }
+            position += len;
+        } else {
+            break;
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Decode what starts at the given position of the file, reading more of the file
+/// while it does not decode, up to MAX_HEADER_READ_SIZE bytes
+fn read_at<T>(
+    file: &ParquetFile,
+    position: usize,
+    decode: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Option<T>, ParquetError> {
+    let remaining = file.len() as usize - position;
+    let mut read_size = min(PAGE_HEADER_READ_SIZE, remaining);
+    loop {
+        let buf = file.get_bytes(position as u64, read_size)?;
+        if let Some(value) = decode(&buf) {
+            return Ok(Some(value));
+        }
+        if read_size == remaining || read_size >= MAX_HEADER_READ_SIZE {
+            return Ok(None);
+        }
+        read_size = min(read_size * 2, remaining);
+    }
+}
+
+/// Decode the header of a page at the start of the buffer, which is complete if it
+/// fits in the given number of bytes left in the file
+fn read_page(buf: &[u8], remaining: usize) -> Option<(PageHeader, usize)> {
+    let (header, header_len) = read_page_header(buf).ok()?;
+    let has_type_header = match header.type_ {
+        PageType::DATA_PAGE => header.data_page_header.is_some(),
+        PageType::DATA_PAGE_V2 => header.data_page_header_v2.is_some(),
+        PageType::DICTIONARY_PAGE => header.dictionary_page_header.is_some(),
+        _ => false,
+    };
+    let len =
+        header_len.checked_add(usize::try_from(header.compressed_page_size).ok()?)?;
+
+    (has_type_header && header.uncompressed_page_size >= 0 && len <= remaining)
+        .then_some((header, header_len))
+}
+
+/// Decode the metadata of a column chunk of the schema at the start of the buffer,
+/// returning the number of bytes it takes up. Writers either put the column metadata
+/// or the whole column chunk struct after the pages of a column chunk.
+fn read_column_metadata(buf: &[u8], schema: &SchemaDescriptor) -> Option<usize> {
+    let (metadata, len) = match read_thrift::<ColumnMetaData>(buf) {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            let (chunk, len) = read_thrift::<ColumnChunk>(buf).ok()?;
+            (chunk.meta_data?, len)
+        }
+    };
+    let path = ColumnPath::new(metadata.path_in_schema);
+    schema
+        .columns()
+        .iter()
+        .any(|column| column.path() == &path)
+        .then_some(len)
+}
+
+/// The parts of the output of schema --json that the recovered file is written with
+#[derive(Deserialize)]
+struct SchemaOutput {
+    metadata: Option<HashMap<String, Option<String>>>,
+    schema: SchemaNode,
+}
+
+/// Read the schema to recover the file with, and the key value metadata of the file it
+/// was taken from, which holds the arrow schema if it was written by arrow
+fn read_schema(path: &Path) -> Result<(SchemaDescPtr, Option<Vec<KeyValue>>), PQRSError> {
+    let file = open_file(path)?;
+    let len = file.len() as usize;
+    if len >= PARQUET_MAGIC.len()
+        && file.get_bytes(0, PARQUET_MAGIC.len())? == PARQUET_MAGIC
+    {
+        let metadata = read_metadata(&file, &FooterArgs::default())?;
+        let file_metadata = metadata.file_metadata();
+        return Ok((
+            file_metadata.schema_descr_ptr(),
+            file_metadata.key_value_metadata().cloned(),
+        ));
+    }
+
+    let text = String::from_utf8(file.get_bytes(0, len)?.to_vec())?;
+    if let Ok(output) = serde_json::from_str::<SchemaOutput>(&text) {
+        let schema = SchemaDescriptor::new(Arc::new(output.schema.to_type()?));
+        let key_value_metadata = output.metadata.map(|metadata| {
+            let mut key_values = metadata
+                .into_iter()
+                .map(|(key, value)| KeyValue { key, value })
+                .collect::<Vec<_>>();
+            key_values.sort_by(|a, b| a.key.cmp(&b.key));
+            key_values
+        });
+        return Ok((Arc::new(schema), key_value_metadata));
+    }
+    if let Ok(schema) = serde_json::from_str::<Schema>(&text) {
+        return Ok((Arc::new(arrow_to_parquet_schema(&schema)?), None));
+    }
+    let message =
+        parse_message_type(&text).map_err(|_| InvalidSchema(path.to_path_buf()))?;
+    Ok((Arc::new(SchemaDescriptor::new(Arc::new(message))), None))
+}
+
+pub(crate) fn execute(opts: RecoverCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file name to recover is: {}", opts.file.display());
+    debug!("The schema is read from: {}", opts.schema.display());
+    debug!("The file name to write to: {}", opts.output.display());
+
+    // make sure output does not exist already before any reads
+    if !is_stdio(&opts.output) && check_path_present(&opts.output) {
+        return Err(FileExists(opts.output));
+    }
+
+    for file_name in [&opts.schema, &opts.file] {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let (schema, key_value_metadata) =
+        read_schema(&opts.schema).map_err(|e| e.in_file(&opts.schema))?;
+
+    let file = open_file(&opts.file)?;
+    let pages =
+        scan_pages(&file, &schema).map_err(|e| PQRSError::from(e).in_file(&opts.file))?;
+    debug!("Found {} pages in {}", pages.len(), opts.file.display());
+
+    let mut assembler = Assembler::new(&file, &schema, &pages);
+    let mut row_groups = vec![];
+    let mut next = 0;
+    while let Some(row_group) = assembler.row_group(next) {
+        next = row_group
+            .chunks
+            .last()
+            .expect("row groups have chunks")
+            .pages
+            .end;
+        row_groups.push(row_group);
+    }
+    if row_groups.is_empty() {
+        return Err(NothingRecovered(opts.file));
+    }
+
+    let props = WriterProperties::builder()
+        .set_key_value_metadata(key_value_metadata)
+        .build();
+    let output = create_output(&opts.output).map_err(|e| e.in_file(&opts.output))?;
+    let mut writer =
+        SerializedFileWriter::new(output, schema.root_schema_ptr(), Arc::new(props))?;
+    for row_group in &row_groups {
+        let mut row_group_writer = writer.next_row_group()?;
+        for (column, chunk) in row_group.chunks.iter().enumerate() {
+            let close = assembler.close_result(column, chunk, row_group.num_rows)?;
+            row_group_writer.append_column(&file, close)?;
+        }
+        row_group_writer.close()?;
+    }
+    writer
+        .close()
+        .map_err(|e| PQRSError::from(e).in_file(&opts.output))?;
+
+    let num_rows: usize = row_groups.iter().map(|row_group| row_group.num_rows).sum();
+    eprintln!(
+        "Recovered {} rows in {} row groups from {}",
+        num_rows,
+        row_groups.len(),
+        opts.file.display()
+    );
+    let recovered_end = pages[next - 1].range().end;
+    let len = file.len() as usize;
+    if recovered_end < len {
+        eprintln!(
+            "Ignored {} bytes from offset {} on, which are not part of a complete row group",
+            len - recovered_end,
+            recovered_end
+        );
+    }
+
+    Ok(())
+}
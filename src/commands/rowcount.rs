@@ -1,17 +1,49 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, get_row_count, open_file};
+use crate::utils::{
+    check_path_present, for_each_file, get_row_count, open_file, FooterArgs, Summary,
+    TOTAL_LABEL,
+};
 use clap::Parser;
 use log::debug;
+use serde::Serialize;
+use std::ops::AddAssign;
 use std::path::PathBuf;
 
 /// Prints the count of rows in Parquet file(s)
 #[derive(Parser, Debug)]
 pub struct RowCountCommandArgs {
-    /// Parquet files to read
+    /// Print a JSON document with the count of each file and their total
+    #[arg(short, long, conflicts_with = "csv")]
+    json: bool,
+
+    /// Print a CSV row with the count of each file and one with their total
+    #[arg(short, long, conflicts_with = "json")]
+    csv: bool,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Number of files to process in parallel, 0 uses one thread per CPU
+    #[arg(long, default_value = "1")]
+    jobs: usize,
+
+    /// Parquet files to read, use - to read from stdin
     files: Vec<PathBuf>,
 }
 
+#[derive(Serialize, Clone, Debug)]
+struct RowCount {
+    file: String,
+    rows: i64,
+}
+
+impl AddAssign for RowCount {
+    fn add_assign(&mut self, rhs: RowCount) {
+        self.rows += rhs.rows;
+    }
+}
+
 pub(crate) fn execute(opts: RowCountCommandArgs) -> Result<(), PQRSError> {
     debug!("The file names to read are: {:?}", opts.files);
 
@@ -22,11 +54,39 @@ pub(crate) fn execute(opts: RowCountCommandArgs) -> Result<(), PQRSError> {
         }
     }
 
-    for file_name in &opts.files {
-        let file = open_file(file_name)?;
-        let row_count = get_row_count(file)?;
-        println!("File Name: {}: {} rows", file_name.display(), &row_count);
-    }
+    let mut summary = Summary::new(RowCount {
+        file: TOTAL_LABEL.to_string(),
+        rows: 0,
+    });
+    for_each_file(
+        &opts.files,
+        opts.jobs,
+        |file_name| {
+            let file = open_file(file_name)?;
+            let row_count = get_row_count(&file, &opts.footer)?;
+            Ok((file, row_count))
+        },
+        |file_name, (file, row_count)| {
+            if !opts.json && !opts.csv {
+                println!("File Name: {}: {} rows", file_name.display(), &row_count);
+            }
+            summary.add(RowCount {
+                file: file_name.display().to_string(),
+                rows: row_count,
+            });
+            opts.footer.report_io(file_name, &file);
+            Ok(())
+        },
+    )?;
 
-    Ok(())
+    if opts.json {
+        summary.print_json()
+    } else if opts.csv {
+        summary.print_csv()
+    } else {
+        if opts.files.len() > 1 {
+            println!("Total: {} rows", summary.total().rows);
+        }
+        Ok(())
+    }
 }
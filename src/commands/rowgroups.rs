@@ -0,0 +1,246 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, UnknownColumn};
+use crate::utils::{
+    check_path_present, format_min_max, open_file, print_table, read_metadata,
+    FooterArgs, ParquetFile, SizeFormatArgs,
+};
+use clap::Parser;
+use log::debug;
+use parquet::file::metadata::RowGroupMetaData;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Prints the row groups of Parquet file(s) as described by their footer
+#[derive(Parser, Debug)]
+pub struct RowGroupsCommandArgs {
+    /// Also show the min and max of the given columns in each row group, as dotted
+    /// paths, e.g. country.name
+    #[arg(short, long, value_name = "COLUMN")]
+    column: Vec<String>,
+
+    /// Print a JSON document with the row groups of each file
+    #[arg(short, long, conflicts_with_all = ["pretty", "si", "unit"])]
+    json: bool,
+
+    #[command(flatten)]
+    format: SizeFormatArgs,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Parquet files to read, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The row groups of a file
+#[derive(Serialize, Debug)]
+struct FileRowGroups {
+    file: String,
+    row_groups: Vec<RowGroup>,
+}
+
+#[derive(Serialize, Debug)]
+struct RowGroup {
+    row_group: usize,
+    /// The position of the row group in the file it was first written to, which
+    /// differs from its position when row groups were left out or reordered
+    ordinal: Option<i16>,
+    /// Where the row group starts, taken from its first column chunk when the writer
+    /// did not store it
+    file_offset: Option<i64>,
+    num_rows: i64,
+    total_byte_size: i64,
+    compressed_size: i64,
+    sorting_columns: Vec<SortingColumn>,
+    columns: Vec<ColumnStatistics>,
+}
+
+/// A column the rows of the row group are sorted by
+#[derive(Serialize, Debug)]
+struct SortingColumn {
+    column: String,
+    descending: bool,
+    nulls_first: bool,
+}
+
+/// The statistics of a column chunk, with the values as they are stored
+#[derive(Serialize, Debug)]
+struct ColumnStatistics {
+    column: String,
+    min: Option<String>,
+    max: Option<String>,
+    null_count: Option<u64>,
+}
+
+pub(crate) fn execute(opts: RowGroupsCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to read are: {:?}", opts.files);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    for (i, file_name) in opts.files.iter().enumerate() {
+        if i > 0 && !opts.json {
+            println!();
+        }
+        let file = open_file(file_name)?;
+        let row_groups = read_row_groups(&file, &opts.footer, &opts.column)
+            .map_err(|e| e.in_file(file_name))?;
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&row_groups)?);
+        } else {
+            print_row_groups(&row_groups, &opts.column, opts.format);
+        }
+        opts.footer.report_io(file_name, &file);
+    }
+
+    Ok(())
+}
+
+fn read_row_groups(
+    file: &ParquetFile,
+    footer: &FooterArgs,
+    columns: &[String],
+) -> Result<FileRowGroups, PQRSError> {
+    let metadata = read_metadata(file, footer)?;
+    let schema = metadata.file_metadata().schema_descr();
+    let mut indexes = vec![];
+    for column in columns {
+        let index = schema
+            .columns()
+            .iter()
+            .position(|c| &c.path().string() == column)
+            .ok_or_else(|| UnknownColumn(column.to_string()))?;
+        indexes.push(index);
+    }
+
+    Ok(FileRowGroups {
+        file: file.path().display().to_string(),
+        row_groups: metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .map(|(row_group, metadata)| {
+                describe_row_group(row_group, metadata, &indexes)
+            })
+            .collect(),
+    })
+}
+
+fn describe_row_group(
+    row_group: usize,
+    metadata: &RowGroupMetaData,
+    indexes: &[usize],
+) -> RowGroup {
+    let column_path = |index: usize| {
+        metadata
+            .columns()
+            .get(index)
+            .map_or_else(|| format!("#{}", index), |c| c.column_path().string())
+    };
+
+    RowGroup {
+        row_group,
+        ordinal: metadata.ordinal(),
+        file_offset: metadata.file_offset().or_else(|| {
+            metadata
+                .columns()
+                .first()
+                .map(|column| column.byte_range().0 as i64)
+        }),
+        num_rows: metadata.num_rows(),
+        total_byte_size: metadata.total_byte_size(),
+        compressed_size: metadata.compressed_size(),
+        sorting_columns: metadata
+            .sorting_columns()
+            .into_iter()
+            .flatten()
+            .map(|sorting| SortingColumn {
+                column: column_path(sorting.column_idx.max(0) as usize),
+                descending: sorting.descending,
+                nulls_first: sorting.nulls_first,
+            })
+            .collect(),
+        columns: indexes
+            .iter()
+            .map(|&index| {
+                let statistics = metadata.column(index).statistics();
+                let min_max = statistics.and_then(format_min_max);
+                ColumnStatistics {
+                    column: column_path(index),
+                    min: min_max.as_ref().map(|(min, _)| min.to_string()),
+                    max: min_max.map(|(_, max)| max),
+                    null_count: statistics.map(|s| s.null_count()),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn print_row_groups(
+    row_groups: &FileRowGroups,
+    columns: &[String],
+    format: SizeFormatArgs,
+) {
+    println!("File Name: {}", row_groups.file);
+
+    let mut header = vec![
+        "Row Group".to_string(),
+        "Ordinal".to_string(),
+        "Offset".to_string(),
+        "Rows".to_string(),
+        "Uncompressed".to_string(),
+        "Compressed".to_string(),
+        "Sorted By".to_string(),
+    ];
+    let mut numeric = vec![true, true, true, true, true, true, false];
+    for column in columns {
+        header.push(format!("{} Min", column));
+        header.push(format!("{} Max", column));
+        numeric.extend([false, false]);
+    }
+
+    let mut rows = vec![header];
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    for row_group in &row_groups.row_groups {
+        let sorted_by = row_group
+            .sorting_columns
+            .iter()
+            .map(|sorting| {
+                format!(
+                    "{} {}{}",
+                    sorting.column,
+                    if sorting.descending { "DESC" } else { "ASC" },
+                    if sorting.nulls_first {
+                        " NULLS FIRST"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut row = vec![
+            row_group.row_group.to_string(),
+            optional(row_group.ordinal.map(|ordinal| ordinal.to_string())),
+            optional(row_group.file_offset.map(|offset| offset.to_string())),
+            row_group.num_rows.to_string(),
+            format.format(row_group.total_byte_size),
+            format.format(row_group.compressed_size),
+            if sorted_by.is_empty() {
+                "-".to_string()
+            } else {
+                sorted_by.join(", ")
+            },
+        ];
+        for column in &row_group.columns {
+            row.push(optional(column.min.clone()));
+            row.push(optional(column.max.clone()));
+        }
+        rows.push(row);
+    }
+    print_table(&rows, &numeric);
+}
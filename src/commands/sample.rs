@@ -1,6 +1,8 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, open_file, print_rows_random, Formats};
+use crate::progress::Progress;
+use crate::render::{RenderArgs, Renderer};
+use crate::utils::{check_path_present, open_file, print_rows_random, Formats, Skipped};
 use clap::Parser;
 use log::debug;
 use std::path::PathBuf;
@@ -16,11 +18,24 @@ pub struct SampleCommandArgs {
     #[arg(short = 'n', long)]
     records: usize,
 
-    /// Parquet file to read
+    /// Show the progress of reading the file on stderr, when it is a terminal
+    #[arg(long)]
+    progress: bool,
+
+    /// Skip row groups that cannot be read instead of stopping, and print a summary
+    /// of what was skipped at the end
+    #[arg(long)]
+    skip_corrupt: bool,
+
+    #[command(flatten)]
+    render: RenderArgs,
+
+    /// Parquet file to read, use - to read from stdin
     file: PathBuf,
 }
 
 pub(crate) fn execute(opts: SampleCommandArgs) -> Result<(), PQRSError> {
+    let renderer = Renderer::try_new(&opts.render)?;
     let format = if opts.json {
         Formats::Json
     } else {
@@ -36,7 +51,22 @@ pub(crate) fn execute(opts: SampleCommandArgs) -> Result<(), PQRSError> {
     }
 
     let file = open_file(&opts.file)?;
-    print_rows_random(file, opts.records, format)?;
+    let mut progress = Progress::new(opts.progress);
+    progress
+        .add_file(&file)
+        .map_err(|e| e.in_file(&opts.file))?;
+    let mut skipped = Skipped::new(opts.skip_corrupt);
+    print_rows_random(
+        file,
+        opts.records,
+        format,
+        &renderer,
+        &mut progress,
+        &mut skipped,
+    )
+    .map_err(|e| e.in_file(&opts.file))?;
+    progress.finish();
+    skipped.print_summary();
 
     Ok(())
 }
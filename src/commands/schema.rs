@@ -1,19 +1,22 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, open_file};
+use crate::schema_formats::{render_schema, LogicalTypeJson, SchemaFormat, SchemaNode};
+use crate::utils::{
+    check_path_present, for_each_file, open_file, read_metadata, FooterArgs,
+};
 use clap::Parser;
+use linked_hash_set::LinkedHashSet;
 use log::debug;
 use parquet::arrow::parquet_to_arrow_schema;
+use parquet::basic::{Compression, ConvertedType};
 use parquet::file::metadata::ParquetMetaData;
-use parquet::file::reader::FileReader;
-use parquet::file::serialized_reader::SerializedFileReader;
 use parquet::schema::printer::{
     print_file_metadata, print_parquet_metadata, print_schema,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Prints the schema of Parquet file(s)
 #[derive(Parser, Debug)]
@@ -30,21 +33,60 @@ pub struct SchemaCommandArgs {
     #[arg(short, long, conflicts_with = "json")]
     arrow: bool,
 
-    /// Parquet files to read
+    /// Translate into a table definition or schema for another system
+    #[arg(
+        long = "as",
+        value_name = "FORMAT",
+        conflicts_with_all = ["detailed", "json", "arrow"]
+    )]
+    format: Option<SchemaFormat>,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Number of files to process in parallel, 0 uses one thread per CPU
+    #[arg(long, default_value = "1")]
+    jobs: usize,
+
+    /// Parquet files to read, use - to read from stdin
     files: Vec<PathBuf>,
 }
 
+/// The version of the layout printed by --json. Version 1 only had a flat list of
+/// columns, with their name, path, physical and converted type as strings.
+const SCHEMA_JSON_VERSION: u32 = 2;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ParquetSchema {
+    format_version: u32,
     version: i32,
     num_rows: i64,
     // num_columns: usize,
     created_by: Option<String>,
     metadata: Option<HashMap<String, Option<String>>>,
-    columns: Vec<HashMap<String, String>>,
+    schema: SchemaNode,
+    columns: Vec<ColumnInformation>,
     message: String,
 }
 
+/// A leaf column with its levels and how its chunks are stored
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ColumnInformation {
+    path: Vec<String>,
+    physical_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logical_type: Option<LogicalTypeJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    converted_type: Option<String>,
+    repetition: String,
+    max_definition_level: i16,
+    max_repetition_level: i16,
+    /// The encodings used by any of the column chunks
+    encodings: Vec<String>,
+    /// The compression codecs used by any of the column chunks
+    compressions: Vec<String>,
+}
+
 fn get_schema_metadata(
     metadata: &ParquetMetaData,
 ) -> Option<HashMap<String, Option<String>>> {
@@ -59,30 +101,48 @@ fn get_schema_metadata(
     }
 }
 
-fn get_column_information(metadata: &ParquetMetaData) -> Vec<HashMap<String, String>> {
+fn get_column_information(metadata: &ParquetMetaData) -> Vec<ColumnInformation> {
     let schema = metadata.file_metadata().schema_descr();
     let mut columns = Vec::new();
-    for (_i, col) in schema.columns().iter().enumerate() {
-        let mut column_info: HashMap<String, String> = HashMap::new();
-        column_info.insert(String::from("name"), String::from(col.name()));
-        column_info.insert(String::from("path"), col.path().string());
-        column_info.insert(
-            String::from("optional"),
-            col.self_type().is_optional().to_string(),
-        );
-        column_info.insert(
-            String::from("physical_type"),
-            col.physical_type().to_string(),
-        );
-        column_info.insert(
-            String::from("converted_type"),
-            col.converted_type().to_string(),
-        );
-        columns.push(column_info)
+    for (index, col) in schema.columns().iter().enumerate() {
+        let mut encodings = LinkedHashSet::new();
+        let mut compressions = LinkedHashSet::new();
+        for row_group in metadata.row_groups() {
+            let chunk = row_group.column(index);
+            for encoding in chunk.encodings() {
+                encodings.insert_if_absent(encoding.to_string());
+            }
+            compressions.insert_if_absent(codec_name(chunk.compression()));
+        }
+
+        let converted_type = match col.converted_type() {
+            ConvertedType::NONE => None,
+            converted_type => Some(converted_type.to_string()),
+        };
+        columns.push(ColumnInformation {
+            path: col.path().parts().to_vec(),
+            physical_type: col.physical_type().to_string(),
+            logical_type: col.logical_type().as_ref().map(LogicalTypeJson::from),
+            converted_type,
+            repetition: col.self_type().get_basic_info().repetition().to_string(),
+            max_definition_level: col.max_def_level(),
+            max_repetition_level: col.max_rep_level(),
+            encodings: encodings.into_iter().collect(),
+            compressions: compressions.into_iter().collect(),
+        })
     }
     columns
 }
 
+/// The name of the codec, without the level it was configured with when reading
+fn codec_name(compression: Compression) -> String {
+    let name = compression.to_string();
+    match name.find('(') {
+        Some(level) => name[..level].to_string(),
+        None => name,
+    }
+}
+
 fn get_message(metadata: &ParquetMetaData) -> Result<String, PQRSError> {
     let mut buf = BufWriter::new(Vec::new());
     print_schema(&mut buf, metadata.file_metadata().schema());
@@ -101,51 +161,67 @@ pub(crate) fn execute(opts: SchemaCommandArgs) -> Result<(), PQRSError> {
         }
     }
 
-    for file_name in &opts.files {
-        let file = open_file(file_name)?;
-        match SerializedFileReader::new(file) {
-            Err(e) => return Err(PQRSError::ParquetError(e)),
-            Ok(parquet_reader) => {
-                if opts.arrow {
-                    // returns a arrow_schema::Schema
-                    // but only arrow::datatypes::Schema is json serializable?
-                    let arrow_schema = parquet_to_arrow_schema(
-                        parquet_reader.metadata().file_metadata().schema_descr(), None
-                    ).unwrap();
-                    let arrow_schema_json = serde_json::to_string_pretty(&arrow_schema).unwrap();
-                    println!("{}", arrow_schema_json);
-                } else {
-
-                    let metadata = parquet_reader.metadata();
-                    if opts.json {
-                        let schema = ParquetSchema {
-                            version: metadata.file_metadata().version(),
-                            num_rows: metadata.file_metadata().num_rows(),
-                            created_by: metadata
-                                .file_metadata()
-                                .created_by()
-                                .map(|str| str.to_string()),
-                            metadata: get_schema_metadata(metadata),
-                            columns: get_column_information(metadata),
-                            message: get_message(metadata)?,
-                        };
-
-                        let schema_json = serde_json::to_string_pretty(&schema)?;
-                        println!("{}", schema_json);
-                    } else {
-                        println!("Metadata for file: {}", file_name.display());
-                        println!();
-                        if opts.detailed {
-                            print_parquet_metadata(&mut std::io::stdout(), metadata);
-                        } else {
-                            print_file_metadata(
-                                &mut std::io::stdout(),
-                                metadata.file_metadata(),
-                            );
-                        }
-                    }
-                }
-            }
+    for_each_file(
+        &opts.files,
+        opts.jobs,
+        |file_name| {
+            let file = open_file(file_name)?;
+            let metadata = read_metadata(&file, &opts.footer)?;
+            Ok((file, metadata))
+        },
+        |file_name, (file, metadata)| {
+            print_schema_output(&opts, file_name, &metadata)?;
+            opts.footer.report_io(file_name, &file);
+            Ok(())
+        },
+    )
+}
+
+/// Print the schema of a single file in the requested format
+fn print_schema_output(
+    opts: &SchemaCommandArgs,
+    file_name: &Path,
+    metadata: &ParquetMetaData,
+) -> Result<(), PQRSError> {
+    if let Some(format) = opts.format {
+        // the Arrow schema stored by the writer keeps types that Parquet has no
+        // logical type for, e.g. durations
+        let arrow_schema = parquet_to_arrow_schema(
+            metadata.file_metadata().schema_descr(),
+            metadata.file_metadata().key_value_metadata(),
+        )?;
+        println!("{}", render_schema(format, file_name, &arrow_schema)?);
+    } else if opts.arrow {
+        // returns a arrow_schema::Schema
+        // but only arrow::datatypes::Schema is json serializable?
+        let arrow_schema =
+            parquet_to_arrow_schema(metadata.file_metadata().schema_descr(), None)?;
+        let arrow_schema_json = serde_json::to_string_pretty(&arrow_schema)?;
+        println!("{}", arrow_schema_json);
+    } else if opts.json {
+        let schema = ParquetSchema {
+            format_version: SCHEMA_JSON_VERSION,
+            version: metadata.file_metadata().version(),
+            num_rows: metadata.file_metadata().num_rows(),
+            created_by: metadata
+                .file_metadata()
+                .created_by()
+                .map(|str| str.to_string()),
+            metadata: get_schema_metadata(metadata),
+            schema: metadata.file_metadata().schema().into(),
+            columns: get_column_information(metadata),
+            message: get_message(metadata)?,
+        };
+
+        let schema_json = serde_json::to_string_pretty(&schema)?;
+        println!("{}", schema_json);
+    } else {
+        println!("Metadata for file: {}", file_name.display());
+        println!();
+        if opts.detailed {
+            print_parquet_metadata(&mut std::io::stdout(), metadata);
+        } else {
+            print_file_metadata(&mut std::io::stdout(), metadata.file_metadata());
         }
     }
 
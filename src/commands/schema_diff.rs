@@ -0,0 +1,408 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, IncompatibleSchemas};
+use crate::utils::{check_path_present, open_file, read_metadata, FooterArgs};
+use clap::{Parser, ValueEnum};
+use log::debug;
+use parquet::basic::{ConvertedType, LogicalType, TimeUnit, Type as PhysicalType};
+use parquet::schema::types::{ColumnDescriptor, SchemaDescPtr};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Compares the schemas of Parquet files and checks that they are compatible
+#[derive(Parser, Debug)]
+pub struct SchemaDiffCommandArgs {
+    /// The compatibility every schema must have with the schema of the previous file,
+    /// the command fails otherwise
+    #[arg(short, long, value_enum, default_value_t = Requirement::Backward)]
+    require: Requirement,
+
+    /// Print a JSON report for each pair of files
+    #[arg(short, long)]
+    json: bool,
+
+    /// Parquet files to compare, each schema is compared with the one of the file before
+    #[arg(num_args = 2..)]
+    files: Vec<PathBuf>,
+}
+
+/// The compatibility a schema change is required to have
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Requirement {
+    None,
+    /// Readers using the new schema can read data written with the old one
+    Backward,
+    /// Readers using the old schema can read data written with the new one
+    Forward,
+    /// Both backward and forward
+    Full,
+}
+
+/// How readers of one schema are affected by data written with the other
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Compatibility {
+    Full,
+    Backward,
+    Forward,
+    Breaking,
+}
+
+impl Compatibility {
+    /// The compatibility of a schema with both changes
+    fn and(self, other: Compatibility) -> Compatibility {
+        match (self, other) {
+            (Compatibility::Full, other) | (other, Compatibility::Full) => other,
+            (a, b) if a == b => a,
+            _ => Compatibility::Breaking,
+        }
+    }
+
+    fn satisfies(self, requirement: Requirement) -> bool {
+        match requirement {
+            Requirement::None => true,
+            Requirement::Backward => {
+                matches!(self, Compatibility::Full | Compatibility::Backward)
+            }
+            Requirement::Forward => {
+                matches!(self, Compatibility::Full | Compatibility::Forward)
+            }
+            Requirement::Full => self == Compatibility::Full,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Compatibility::Full => "fully compatible",
+            Compatibility::Backward => "backward compatible",
+            Compatibility::Forward => "forward compatible",
+            Compatibility::Breaking => "breaking",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct ColumnChange {
+    column: String,
+    change: String,
+    compatibility: Compatibility,
+}
+
+#[derive(Serialize, Debug)]
+struct SchemaComparison {
+    from: String,
+    to: String,
+    compatibility: Compatibility,
+    changes: Vec<ColumnChange>,
+}
+
+/// The kind of values of a column, for finding the type changes that widen the values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Integer { bits: i8, signed: bool },
+    Float,
+    Double,
+    Decimal { precision: i32, scale: i32 },
+    Other,
+}
+
+fn value_kind(column: &ColumnDescriptor) -> ValueKind {
+    let integer = |bits, signed| ValueKind::Integer { bits, signed };
+    let decimal = ValueKind::Decimal {
+        precision: column.type_precision(),
+        scale: column.type_scale(),
+    };
+    match (column.logical_type(), column.converted_type()) {
+        (Some(LogicalType::Decimal { .. }), _) | (None, ConvertedType::DECIMAL) => {
+            decimal
+        }
+        (
+            Some(LogicalType::Integer {
+                bit_width,
+                is_signed,
+            }),
+            _,
+        ) => integer(bit_width, is_signed),
+        (None, ConvertedType::INT_8) => integer(8, true),
+        (None, ConvertedType::INT_16) => integer(16, true),
+        (None, ConvertedType::INT_32) => integer(32, true),
+        (None, ConvertedType::INT_64) => integer(64, true),
+        (None, ConvertedType::UINT_8) => integer(8, false),
+        (None, ConvertedType::UINT_16) => integer(16, false),
+        (None, ConvertedType::UINT_32) => integer(32, false),
+        (None, ConvertedType::UINT_64) => integer(64, false),
+        (None, ConvertedType::NONE) => match column.physical_type() {
+            PhysicalType::INT32 => integer(32, true),
+            PhysicalType::INT64 => integer(64, true),
+            PhysicalType::FLOAT => ValueKind::Float,
+            PhysicalType::DOUBLE => ValueKind::Double,
+            _ => ValueKind::Other,
+        },
+        _ => ValueKind::Other,
+    }
+}
+
+/// Whether every value of the `from` kind can be represented exactly by the `to` kind
+fn widens(from: ValueKind, to: ValueKind) -> bool {
+    match (from, to) {
+        (
+            ValueKind::Integer { bits, signed },
+            ValueKind::Integer {
+                bits: to_bits,
+                signed: to_signed,
+            },
+        ) => to_bits > bits && (to_signed || !signed),
+        (ValueKind::Integer { bits, .. }, ValueKind::Double) => bits <= 32,
+        (ValueKind::Float, ValueKind::Double) => true,
+        (
+            ValueKind::Decimal { precision, scale },
+            ValueKind::Decimal {
+                precision: to_precision,
+                scale: to_scale,
+            },
+        ) => {
+            (precision, scale) != (to_precision, to_scale)
+                && to_scale >= scale
+                && to_precision - to_scale >= precision - scale
+        }
+        _ => false,
+    }
+}
+
+fn describe_time_unit(unit: &TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::MILLIS(_) => "MILLIS",
+        TimeUnit::MICROS(_) => "MICROS",
+        TimeUnit::NANOS(_) => "NANOS",
+    }
+}
+
+/// Describe the physical type of the column with its logical or converted type,
+/// e.g. `INT64 TIMESTAMP(MICROS,true)` or `INT32 DECIMAL(9,2)`
+fn describe_type(column: &ColumnDescriptor) -> String {
+    let physical = match column.physical_type() {
+        PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+            format!("FIXED_LEN_BYTE_ARRAY({})", column.type_length())
+        }
+        physical => physical.to_string(),
+    };
+    let logical = match column.logical_type() {
+        Some(LogicalType::Integer {
+            bit_width,
+            is_signed,
+        }) => format!("INTEGER({},{})", bit_width, is_signed),
+        Some(LogicalType::Decimal { scale, precision }) => {
+            format!("DECIMAL({},{})", precision, scale)
+        }
+        Some(LogicalType::Timestamp {
+            is_adjusted_to_u_t_c,
+            unit,
+        }) => format!(
+            "TIMESTAMP({},{})",
+            describe_time_unit(&unit),
+            is_adjusted_to_u_t_c
+        ),
+        Some(LogicalType::Time {
+            is_adjusted_to_u_t_c,
+            unit,
+        }) => format!(
+            "TIME({},{})",
+            describe_time_unit(&unit),
+            is_adjusted_to_u_t_c
+        ),
+        Some(logical) => format!("{:?}", logical).to_uppercase(),
+        None => match column.converted_type() {
+            ConvertedType::NONE => String::new(),
+            ConvertedType::DECIMAL => format!(
+                "DECIMAL({},{})",
+                column.type_precision(),
+                column.type_scale()
+            ),
+            converted => converted.to_string(),
+        },
+    };
+    if logical.is_empty() {
+        physical
+    } else {
+        format!("{} {}", physical, logical)
+    }
+}
+
+/// Columns without optional or repeated ancestors always have a value
+fn is_required(column: &ColumnDescriptor) -> bool {
+    column.max_def_level() == 0
+}
+
+fn same_type(from: &ColumnDescriptor, to: &ColumnDescriptor) -> bool {
+    from.physical_type() == to.physical_type()
+        && from.logical_type() == to.logical_type()
+        && from.converted_type() == to.converted_type()
+        && from.type_length() == to.type_length()
+        && from.type_precision() == to.type_precision()
+        && from.type_scale() == to.type_scale()
+}
+
+/// The changes to a column present in both schemas
+fn column_changes(
+    from: &ColumnDescriptor,
+    to: &ColumnDescriptor,
+) -> Vec<(String, Compatibility)> {
+    let mut changes = vec![];
+
+    if from.max_rep_level() != to.max_rep_level() {
+        let change = format!(
+            "nesting changed from {} to {} repeated levels",
+            from.max_rep_level(),
+            to.max_rep_level()
+        );
+        changes.push((change, Compatibility::Breaking));
+    } else if is_required(from) && !is_required(to) {
+        changes.push((String::from("became optional"), Compatibility::Backward));
+    } else if !is_required(from) && is_required(to) {
+        changes.push((String::from("became required"), Compatibility::Forward));
+    }
+
+    if !same_type(from, to) {
+        let (from_kind, to_kind) = (value_kind(from), value_kind(to));
+        let (verb, compatibility) = if widens(from_kind, to_kind) {
+            ("widened", Compatibility::Backward)
+        } else if widens(to_kind, from_kind) {
+            ("narrowed", Compatibility::Forward)
+        } else {
+            ("changed", Compatibility::Breaking)
+        };
+        let change = format!(
+            "type {} from {} to {}",
+            verb,
+            describe_type(from),
+            describe_type(to)
+        );
+        changes.push((change, compatibility));
+    }
+
+    changes
+}
+
+/// Compare the leaf columns of the schemas by their path
+fn compare_schemas(from: &SchemaDescPtr, to: &SchemaDescPtr) -> Vec<ColumnChange> {
+    let mut changes = vec![];
+    let find = |schema: &SchemaDescPtr, column: &ColumnDescriptor| {
+        schema
+            .columns()
+            .iter()
+            .find(|other| other.path() == column.path())
+            .cloned()
+    };
+
+    for column in from.columns() {
+        let name = column.path().string();
+        match find(to, column) {
+            Some(other) => {
+                for (change, compatibility) in column_changes(column, &other) {
+                    changes.push(ColumnChange {
+                        column: name.clone(),
+                        change,
+                        compatibility,
+                    });
+                }
+            }
+            None => {
+                // readers of the old schema cannot do without a required column
+                let (change, compatibility) = if is_required(column) {
+                    ("removed required column", Compatibility::Backward)
+                } else {
+                    ("removed optional column", Compatibility::Full)
+                };
+                changes.push(ColumnChange {
+                    column: name,
+                    change: format!("{} of type {}", change, describe_type(column)),
+                    compatibility,
+                });
+            }
+        }
+    }
+
+    for column in to.columns() {
+        if find(from, column).is_none() {
+            // data written with the old schema has no values for a new required column
+            let (change, compatibility) = if is_required(column) {
+                ("added required column", Compatibility::Forward)
+            } else {
+                ("added optional column", Compatibility::Full)
+            };
+            changes.push(ColumnChange {
+                column: column.path().string(),
+                change: format!("{} of type {}", change, describe_type(column)),
+                compatibility,
+            });
+        }
+    }
+
+    changes
+}
+
+pub(crate) fn execute(opts: SchemaDiffCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to compare are: {:?}", opts.files);
+    debug!("The required compatibility is: {:?}", opts.require);
+
+    // make sure all files are present before printing any data
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let mut schemas = vec![];
+    for file_name in &opts.files {
+        let file = open_file(file_name)?;
+        let metadata = read_metadata(&file, &FooterArgs::default())
+            .map_err(|e| e.in_file(file_name))?;
+        schemas.push(metadata.file_metadata().schema_descr_ptr());
+    }
+
+    let mut failed = 0;
+    for (index, pair) in schemas.windows(2).enumerate() {
+        let changes = compare_schemas(&pair[0], &pair[1]);
+        let compatibility = changes
+            .iter()
+            .fold(Compatibility::Full, |compatibility, change| {
+                compatibility.and(change.compatibility)
+            });
+        if !compatibility.satisfies(opts.require) {
+            failed += 1;
+        }
+
+        let comparison = SchemaComparison {
+            from: opts.files[index].display().to_string(),
+            to: opts.files[index + 1].display().to_string(),
+            compatibility,
+            changes,
+        };
+        if opts.json {
+            println!("{}", serde_json::to_string(&comparison)?);
+        } else if comparison.changes.is_empty() {
+            println!("{} -> {}: identical", comparison.from, comparison.to);
+        } else {
+            println!(
+                "{} -> {}: {}",
+                comparison.from,
+                comparison.to,
+                compatibility.describe()
+            );
+            for change in &comparison.changes {
+                println!(
+                    "  {}: {} ({})",
+                    change.column,
+                    change.change,
+                    change.compatibility.describe()
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        let requirement = format!("{:?}", opts.require).to_lowercase();
+        return Err(IncompatibleSchemas(failed, requirement));
+    }
+
+    Ok(())
+}
@@ -1,25 +1,177 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::FileNotFound;
-use crate::utils::{check_path_present, get_pretty_size, get_size, open_file};
-use clap::Parser;
+use crate::utils::{
+    check_path_present, for_each_file, get_size, open_file, print_table, read_footer,
+    read_page_header, FileSize, FooterArgs, ParquetFile, SizeFormatArgs, Summary,
+    TOTAL_LABEL,
+};
+use clap::{Parser, ValueEnum};
 use log::debug;
+use parquet::basic::Encoding;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::format::PageType;
+use serde::Serialize;
+use std::ops::AddAssign;
 use std::path::PathBuf;
 
+/// The bytes read to find a dictionary page header, which holds no statistics
+static MAX_DICTIONARY_HEADER_SIZE: i64 = 1024;
+
 /// Prints the size of Parquet file(s)
 #[derive(Parser, Debug)]
 pub struct SizeCommandArgs {
-    /// Show pretty, human readable size
-    #[arg(short, long)]
-    pretty: bool,
+    #[command(flatten)]
+    format: SizeFormatArgs,
 
-    /// Show compressed size
+    /// Show compressed size, together with the uncompressed size if that is asked for
     #[arg(short, long)]
     compressed: bool,
 
-    /// Parquet files to read
+    /// Show uncompressed size, which is the default unless --compressed is given
+    #[arg(short, long)]
+    uncompressed: bool,
+
+    /// Break the size down per column or per row group
+    #[arg(
+        long,
+        value_name = "BREAKDOWN",
+        conflicts_with_all = ["compressed", "uncompressed", "json", "csv"]
+    )]
+    by: Option<Breakdown>,
+
+    /// Print a JSON document with the sizes of each file and their total in bytes
+    #[arg(short, long, conflicts_with_all = ["csv", "pretty", "si", "unit"])]
+    json: bool,
+
+    /// Print a CSV row with the sizes of each file and one with their total in bytes
+    #[arg(long, conflicts_with_all = ["json", "pretty", "si", "unit"])]
+    csv: bool,
+
+    #[command(flatten)]
+    footer: FooterArgs,
+
+    /// Number of files to process in parallel, 0 uses one thread per CPU
+    #[arg(long, default_value = "1")]
+    jobs: usize,
+
+    /// Parquet files to read, use - to read from stdin
     files: Vec<PathBuf>,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Breakdown {
+    /// One line per column, summed over all row groups
+    Column,
+    /// One line per row group, summed over all columns
+    RowGroup,
+}
+
+/// The sizes of a file as printed in JSON and CSV, which always hold all of them
+#[derive(Serialize, Clone, Debug)]
+struct SizeRecord {
+    file: String,
+    uncompressed: i64,
+    compressed: i64,
+    file_size: i64,
+    page_index: i64,
+    bloom_filters: i64,
+    footer: i64,
+    other: i64,
+}
+
+impl SizeRecord {
+    fn new(file: String, size: &FileSize) -> SizeRecord {
+        SizeRecord {
+            file,
+            uncompressed: size.uncompressed,
+            compressed: size.compressed,
+            file_size: size.file,
+            page_index: size.page_index,
+            bloom_filters: size.bloom_filters,
+            footer: size.footer,
+            other: size.other(),
+        }
+    }
+}
+
+impl AddAssign for SizeRecord {
+    fn add_assign(&mut self, rhs: SizeRecord) {
+        self.uncompressed += rhs.uncompressed;
+        self.compressed += rhs.compressed;
+        self.file_size += rhs.file_size;
+        self.page_index += rhs.page_index;
+        self.bloom_filters += rhs.bloom_filters;
+        self.footer += rhs.footer;
+        self.other += rhs.other;
+    }
+}
+
+/// Which of the sizes of the data to print
+#[derive(Debug, Clone, Copy)]
+struct Shown {
+    uncompressed: bool,
+    compressed: bool,
+}
+
+/// The bytes of one or more column chunks
+#[derive(Debug, Default, Clone, Copy)]
+struct ChunkSizes {
+    compressed: i64,
+    uncompressed: i64,
+    /// The compressed bytes of the dictionary pages, including their headers
+    dictionary: i64,
+}
+
+impl ChunkSizes {
+    fn add(
+        &mut self,
+        file: &ParquetFile,
+        column: &ColumnChunkMetaData,
+    ) -> Result<(), PQRSError> {
+        self.compressed += column.compressed_size();
+        self.uncompressed += column.uncompressed_size();
+        self.dictionary += dictionary_size(file, column)?;
+        Ok(())
+    }
+}
+
+/// The chunk sizes summed per column or per row group, labelled by either
+type SizeBreakdown = Vec<(String, ChunkSizes)>;
+
+/// The bytes of the dictionary page of the chunk, including its header. The dictionary
+/// page comes first in the chunk, right before the data pages.
+fn dictionary_size(
+    file: &ParquetFile,
+    column: &ColumnChunkMetaData,
+) -> Result<i64, PQRSError> {
+    if let Some(dictionary_offset) = column.dictionary_page_offset() {
+        return Ok(column.data_page_offset() - dictionary_offset);
+    }
+    let dictionary_encoded = column.encodings().iter().any(|encoding| {
+        matches!(
+            encoding,
+            Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+        )
+    });
+    if !dictionary_encoded {
+        return Ok(0);
+    }
+
+    // some writers leave out the dictionary page offset and point the data page offset
+    // at the dictionary page instead
+    let length = column
+        .compressed_size()
+        .clamp(0, MAX_DICTIONARY_HEADER_SIZE) as usize;
+    let bytes = file.get_bytes(column.data_page_offset() as u64, length)?;
+    match read_page_header(&bytes) {
+        Ok((header, header_length)) if header.type_ == PageType::DICTIONARY_PAGE => {
+            Ok(header_length as i64 + header.compressed_page_size as i64)
+        }
+        _ => Ok(0),
+    }
+}
+
 pub(crate) fn execute(opts: SizeCommandArgs) -> Result<(), PQRSError> {
     debug!("The file names to read are: {:?}", opts.files);
 
@@ -30,26 +182,176 @@ pub(crate) fn execute(opts: SizeCommandArgs) -> Result<(), PQRSError> {
         }
     }
 
-    println!("Size in Bytes:");
-    for file_name in &opts.files {
-        let file = open_file(file_name)?;
-        let size_info = get_size(file)?;
+    let text = !opts.json && !opts.csv;
+    let shown = Shown {
+        uncompressed: opts.uncompressed || !opts.compressed,
+        compressed: opts.compressed,
+    };
+    // the heading is printed with the first file, so nothing is printed when its
+    // footer cannot be read
+    let mut heading = text;
+    let mut total = FileSize::default();
+    let mut summary = Summary::new(SizeRecord::new(
+        TOTAL_LABEL.to_string(),
+        &FileSize::default(),
+    ));
+    for_each_file(
+        &opts.files,
+        opts.jobs,
+        |file_name| {
+            let file = open_file(file_name)?;
+            let (size_info, breakdown) =
+                read_sizes(&file, &opts).map_err(|e| e.in_file(file_name))?;
+            Ok((file, size_info, breakdown))
+        },
+        |file_name, (file, size_info, breakdown)| {
+            if !text {
+                summary.add(SizeRecord::new(file_name.display().to_string(), &size_info));
+                opts.footer.report_io(file_name, &file);
+                return Ok(());
+            }
 
-        println!();
-        println!("File Name: {}", file_name.display());
+            if heading {
+                println!("Size in Bytes:");
+                heading = false;
+            }
+            println!();
+            println!("File Name: {}", file_name.display());
 
-        if !opts.compressed {
-            if opts.pretty {
-                println!("Uncompressed Size: {}", get_pretty_size(size_info.0));
-            } else {
-                println!("Uncompressed Size: {}", size_info.0);
+            if let Some(breakdown) = breakdown {
+                print_breakdown(&breakdown, opts.by, file.len(), opts.format);
+                opts.footer.report_io(file_name, &file);
+                return Ok(());
             }
-        } else if opts.pretty {
-            println!("Compressed Size: {}", get_pretty_size(size_info.1));
-        } else {
-            println!("Compressed Size: {}", size_info.1);
-        }
+
+            print_size(&size_info, shown, opts.format);
+            total += size_info;
+            opts.footer.report_io(file_name, &file);
+            Ok(())
+        },
+    )?;
+
+    if opts.json {
+        return summary.print_json();
+    }
+    if opts.csv {
+        return summary.print_csv();
+    }
+    if opts.by.is_none() && opts.files.len() > 1 {
+        println!();
+        println!("Total ({} files):", opts.files.len());
+        print_size(&total, shown, opts.format);
     }
 
     Ok(())
 }
+
+/// Read the footer of the file and the sizes it gives, broken down when asked
+fn read_sizes(
+    file: &ParquetFile,
+    opts: &SizeCommandArgs,
+) -> Result<(FileSize, Option<SizeBreakdown>), PQRSError> {
+    let footer = read_footer(file, &opts.footer)?;
+    let size_info = get_size(file, &footer)?;
+    let breakdown = match opts.by {
+        Some(by) => Some(get_breakdown(file, &footer.metadata, by)?),
+        None => None,
+    };
+    Ok((size_info, breakdown))
+}
+
+/// Print the size of the data followed by what the bytes of the file hold
+fn print_size(size: &FileSize, shown: Shown, format: SizeFormatArgs) {
+    if shown.uncompressed {
+        println!("Uncompressed Size: {}", format.format(size.uncompressed));
+    }
+    if shown.compressed {
+        println!("Compressed Size: {}", format.format(size.compressed));
+    }
+    println!("File Size: {}", format.format(size.file));
+    println!("  Page Index: {}", format.format(size.page_index));
+    println!("  Bloom Filters: {}", format.format(size.bloom_filters));
+    println!("  Footer: {}", format.format(size.footer));
+    println!("  Other: {}", format.format(size.other()));
+}
+
+/// The sizes of the column chunks, summed per column or per row group
+fn get_breakdown(
+    file: &ParquetFile,
+    metadata: &ParquetMetaData,
+    by: Breakdown,
+) -> Result<SizeBreakdown, PQRSError> {
+    let mut breakdown = match by {
+        Breakdown::Column => metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| (column.path().string(), ChunkSizes::default()))
+            .collect::<Vec<_>>(),
+        Breakdown::RowGroup => (0..metadata.num_row_groups())
+            .map(|row_group| (row_group.to_string(), ChunkSizes::default()))
+            .collect(),
+    };
+
+    for (row_group_index, row_group) in metadata.row_groups().iter().enumerate() {
+        for (column_index, column) in row_group.columns().iter().enumerate() {
+            let index = match by {
+                Breakdown::Column => column_index,
+                Breakdown::RowGroup => row_group_index,
+            };
+            breakdown[index].1.add(file, column)?;
+        }
+    }
+    Ok(breakdown)
+}
+
+fn print_breakdown(
+    breakdown: &[(String, ChunkSizes)],
+    by: Option<Breakdown>,
+    file_size: u64,
+    format: SizeFormatArgs,
+) {
+    let bytes = |bytes: i64| format.format(bytes);
+    let label = match by {
+        Some(Breakdown::RowGroup) => "Row Group",
+        _ => "Column",
+    };
+
+    let mut rows = vec![vec![
+        label.to_string(),
+        "Compressed".to_string(),
+        "Uncompressed".to_string(),
+        "Ratio".to_string(),
+        "% of File".to_string(),
+        "Dictionary".to_string(),
+        "Data".to_string(),
+    ]];
+    for (name, sizes) in breakdown {
+        let ratio = if sizes.compressed > 0 {
+            format!(
+                "{:.2}x",
+                sizes.uncompressed as f64 / sizes.compressed as f64
+            )
+        } else {
+            "-".to_string()
+        };
+        let share = if file_size > 0 {
+            format!("{:.1}%", sizes.compressed as f64 * 100.0 / file_size as f64)
+        } else {
+            "-".to_string()
+        };
+        rows.push(vec![
+            name.to_string(),
+            bytes(sizes.compressed),
+            bytes(sizes.uncompressed),
+            ratio,
+            share,
+            bytes(sizes.dictionary),
+            bytes(sizes.compressed - sizes.dictionary),
+        ]);
+    }
+
+    // the name is aligned left and the numbers right
+    print_table(&rows, &[false, true, true, true, true, true, true]);
+}
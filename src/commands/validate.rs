@@ -0,0 +1,471 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, InvalidContract, ValidationFailed};
+use crate::utils::{check_path_present, open_file, ParquetFile};
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Schema};
+use clap::Parser;
+use log::debug;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::{parquet_to_arrow_schema, ProjectionMask};
+use parquet::file::reader::{ChunkReader, Length};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Validates Parquet file(s) against a schema contract
+#[derive(Parser, Debug)]
+pub struct ValidateCommandArgs {
+    /// The contract, either the output of schema --arrow or a JSON object with a
+    /// schema and column constraints, use - to read from stdin
+    #[arg(short, long)]
+    schema: PathBuf,
+
+    /// Print a JSON report for each file
+    #[arg(short, long)]
+    json: bool,
+
+    /// Parquet files to validate, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+/// The expected schema and the constraints on the values of top level columns
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Contract {
+    /// The expected Arrow schema, in the format of schema --arrow
+    schema: Option<Schema>,
+    /// Whether the files may have columns that are not in the expected schema
+    allow_extra_columns: bool,
+    columns: BTreeMap<String, Constraints>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Constraints {
+    nullable: Option<bool>,
+    min: Option<f64>,
+    max: Option<f64>,
+    pattern: Option<String>,
+    allowed: Option<Vec<Value>>,
+    unique: bool,
+    #[serde(skip)]
+    regex: Option<Regex>,
+}
+
+impl Constraints {
+    /// Whether any constraint needs the values of the column to be read
+    fn reads_values(&self) -> bool {
+        self.nullable == Some(false)
+            || self.min.is_some()
+            || self.max.is_some()
+            || self.pattern.is_some()
+            || self.allowed.is_some()
+            || self.unique
+    }
+}
+
+/// A constraint of the contract that a column of the file does not meet
+#[derive(Serialize, Debug)]
+struct Violation {
+    column: String,
+    check: &'static str,
+    message: String,
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    file: String,
+    valid: bool,
+    violations: Vec<Violation>,
+}
+
+pub(crate) fn execute(opts: ValidateCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to validate are: {:?}", opts.files);
+    debug!("The contract to validate against is: {:?}", opts.schema);
+
+    // make sure all files are present before validating any of them
+    for file_name in std::iter::once(&opts.schema).chain(&opts.files) {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let contract = read_contract(&opts.schema)?;
+
+    let mut failed = 0;
+    for file_name in &opts.files {
+        let file = open_file(file_name)?;
+        let report = validate_file(&contract, &file).map_err(|e| e.in_file(file_name))?;
+        if !report.valid {
+            failed += 1;
+        }
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            print_report(&report);
+        }
+    }
+
+    if failed > 0 {
+        return Err(ValidationFailed(failed, opts.files.len()));
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &Report) {
+    let verdict = if report.valid { "valid" } else { "invalid" };
+    println!("File: {}: {}", report.file, verdict);
+    for violation in &report.violations {
+        println!("  {}: {}", violation.column, violation.message);
+    }
+}
+
+/// Read a contract, a bare Arrow schema is a contract without column constraints
+fn read_contract(path: &Path) -> Result<Contract, PQRSError> {
+    let invalid = |message: String| InvalidContract(path.to_path_buf(), message);
+    let file = open_file(path)?;
+    let text = String::from_utf8(file.get_bytes(0, file.len() as usize)?.to_vec())
+        .map_err(|e| invalid(e.to_string()))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+
+    let mut contract = if value.get("fields").is_some() {
+        Contract {
+            schema: Some(
+                serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?,
+            ),
+            ..Default::default()
+        }
+    } else {
+        serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?
+    };
+
+    for (name, constraints) in contract.columns.iter_mut() {
+        if let Some(pattern) = &constraints.pattern {
+            // anchor the pattern so that it has to match the whole value
+            let regex = Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|e| invalid(format!("column {}: {}", name, e)))?;
+            constraints.regex = Some(regex);
+        }
+    }
+
+    Ok(contract)
+}
+
+fn validate_file(contract: &Contract, file: &ParquetFile) -> Result<Report, PQRSError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file.try_clone()?)?;
+    // the same conversion as schema --arrow, so that its output validates the file
+    let actual = parquet_to_arrow_schema(builder.parquet_schema(), None)?;
+
+    let mut violations = vec![];
+    let mut missing = HashSet::new();
+    if let Some(expected) = &contract.schema {
+        for field in expected.fields() {
+            let violation = |message: String| Violation {
+                column: field.name().to_string(),
+                check: "schema",
+                message,
+            };
+            match actual.field_with_name(field.name()) {
+                Err(_) => {
+                    missing.insert(field.name().as_str());
+                    violations.push(violation("missing column".to_string()));
+                }
+                Ok(found) => {
+                    if found.data_type() != field.data_type() {
+                        violations.push(violation(format!(
+                            "has type {} but the contract expects {}",
+                            found.data_type(),
+                            field.data_type()
+                        )));
+                    }
+                    if found.is_nullable() && !field.is_nullable() {
+                        violations.push(violation(
+                            "is nullable but the contract expects it not to be"
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+        if !contract.allow_extra_columns {
+            for found in actual.fields() {
+                if expected.field_with_name(found.name()).is_err() {
+                    violations.push(Violation {
+                        column: found.name().to_string(),
+                        check: "schema",
+                        message: "is not in the contract".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut checks = vec![];
+    let mut roots = vec![];
+    for (name, constraints) in &contract.columns {
+        match builder.schema().index_of(name) {
+            Ok(index) => {
+                if constraints.reads_values() {
+                    roots.push(index);
+                    checks.push(ColumnCheck::new(name, constraints));
+                }
+            }
+            Err(_) if !missing.contains(name.as_str()) => violations.push(Violation {
+                column: name.to_string(),
+                check: "schema",
+                message: "missing column".to_string(),
+            }),
+            Err(_) => {}
+        }
+    }
+
+    if !checks.is_empty() {
+        let projection = ProjectionMask::roots(builder.parquet_schema(), roots);
+        for batch in builder.with_projection(projection).build()? {
+            let batch = batch?;
+            for check in checks.iter_mut() {
+                if let Some(array) = batch.column_by_name(check.name) {
+                    check.check(array);
+                }
+            }
+        }
+        for check in checks {
+            violations.extend(check.finish());
+        }
+    }
+
+    Ok(Report {
+        file: file.path().display().to_string(),
+        valid: violations.is_empty(),
+        violations,
+    })
+}
+
+/// The number of values that failed a check and the first of them
+#[derive(Default)]
+struct Tally {
+    count: usize,
+    example: Option<String>,
+}
+
+impl Tally {
+    fn add(&mut self, example: impl FnOnce() -> String) {
+        self.count += 1;
+        if self.example.is_none() {
+            self.example = Some(example());
+        }
+    }
+}
+
+/// The state of checking the constraints of one column across all record batches
+struct ColumnCheck<'a> {
+    name: &'a str,
+    constraints: &'a Constraints,
+    nulls: usize,
+    below: Tally,
+    above: Tally,
+    unmatched: Tally,
+    disallowed: Tally,
+    duplicates: Tally,
+    seen: HashSet<String>,
+    /// A check that could not be run on the type of the column and why
+    unchecked: Option<(&'static str, String)>,
+}
+
+impl<'a> ColumnCheck<'a> {
+    fn new(name: &'a str, constraints: &'a Constraints) -> ColumnCheck<'a> {
+        ColumnCheck {
+            name,
+            constraints,
+            nulls: 0,
+            below: Tally::default(),
+            above: Tally::default(),
+            unmatched: Tally::default(),
+            disallowed: Tally::default(),
+            duplicates: Tally::default(),
+            seen: HashSet::new(),
+            unchecked: None,
+        }
+    }
+
+    fn check(&mut self, array: &ArrayRef) {
+        let constraints = self.constraints;
+        self.nulls += array.null_count();
+
+        let numeric = array.data_type().is_numeric();
+        let allowed = constraints.allowed.as_deref();
+        if constraints.min.is_some() || constraints.max.is_some() {
+            if numeric {
+                self.check_numbers(array, None);
+            } else if self.unchecked.is_none() {
+                let reason = format!("{} is not a numeric type", array.data_type());
+                self.unchecked = Some(("range", reason));
+            }
+        }
+        if numeric && allowed.is_some() {
+            self.check_numbers(array, allowed);
+        }
+        if constraints.regex.is_some()
+            || constraints.unique
+            || (!numeric && allowed.is_some())
+        {
+            self.check_strings(array, if numeric { None } else { allowed });
+        }
+    }
+
+    /// Check the range of the values, and that they are allowed if `allowed` is given
+    fn check_numbers(&mut self, array: &ArrayRef, allowed: Option<&[Value]>) {
+        let numbers = match cast(array, &DataType::Float64) {
+            Ok(numbers) => numbers,
+            Err(e) => {
+                self.unchecked.get_or_insert(("range", e.to_string()));
+                return;
+            }
+        };
+        let example = |index: usize| {
+            move || {
+                arrow::util::display::array_value_to_string(array, index)
+                    .unwrap_or_default()
+            }
+        };
+
+        for (index, number) in numbers.as_primitive::<Float64Type>().iter().enumerate() {
+            let number = match number {
+                Some(number) => number,
+                None => continue,
+            };
+            if let Some(allowed) = allowed {
+                if !allowed.iter().any(|value| value.as_f64() == Some(number)) {
+                    self.disallowed.add(example(index));
+                }
+                continue;
+            }
+            if self.constraints.min.is_some_and(|min| number < min) {
+                self.below.add(example(index));
+            }
+            if self.constraints.max.is_some_and(|max| number > max) {
+                self.above.add(example(index));
+            }
+        }
+    }
+
+    /// Check the pattern and uniqueness of the values, and that they are allowed if
+    /// `allowed` is given. Values of other types are compared as they are displayed.
+    fn check_strings(&mut self, array: &ArrayRef, allowed: Option<&[Value]>) {
+        let allowed = allowed.map(|allowed| {
+            allowed
+                .iter()
+                .map(|value| match value {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+        });
+        let strings = match cast(array, &DataType::Utf8) {
+            Ok(strings) => strings,
+            Err(e) => {
+                self.unchecked.get_or_insert(("values", e.to_string()));
+                return;
+            }
+        };
+        let quoted = matches!(array.data_type(), DataType::Utf8 | DataType::LargeUtf8);
+        let example = |value: &str| {
+            let value = value.to_string();
+            move || {
+                if quoted {
+                    format!("{:?}", value)
+                } else {
+                    value
+                }
+            }
+        };
+
+        for value in strings.as_string::<i32>().iter().flatten() {
+            if let Some(regex) = &self.constraints.regex {
+                if !regex.is_match(value) {
+                    self.unmatched.add(example(value));
+                }
+            }
+            if let Some(allowed) = &allowed {
+                if !allowed.iter().any(|allowed| allowed == value) {
+                    self.disallowed.add(example(value));
+                }
+            }
+            if self.constraints.unique && !self.seen.insert(value.to_string()) {
+                self.duplicates.add(example(value));
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<Violation> {
+        let (name, constraints) = (self.name, self.constraints);
+        let mut violations = vec![];
+        let mut violation = |check: &'static str, message: String| {
+            violations.push(Violation {
+                column: name.to_string(),
+                check,
+                message,
+            })
+        };
+
+        if let Some((check, reason)) = self.unchecked {
+            violation(check, format!("could not be checked: {}", reason));
+        }
+        if constraints.nullable == Some(false) && self.nulls > 0 {
+            violation("nullable", count(self.nulls, "null value"));
+        }
+        let failed = [
+            (
+                "min",
+                self.below,
+                "value",
+                format!(" below {}", limit(constraints.min)),
+            ),
+            (
+                "max",
+                self.above,
+                "value",
+                format!(" above {}", limit(constraints.max)),
+            ),
+            (
+                "pattern",
+                self.unmatched,
+                "value",
+                " not matching the pattern".to_string(),
+            ),
+            (
+                "allowed",
+                self.disallowed,
+                "value",
+                " not allowed".to_string(),
+            ),
+            ("unique", self.duplicates, "duplicate value", String::new()),
+        ];
+        for (check, tally, noun, description) in failed {
+            if let Some(example) = tally.example {
+                let message = format!("{}{}", count(tally.count, noun), description);
+                violation(check, format!("{}, e.g. {}", message, example));
+            }
+        }
+
+        violations
+    }
+}
+
+fn limit(limit: Option<f64>) -> String {
+    limit.map(|limit| limit.to_string()).unwrap_or_default()
+}
+
+/// The count followed by the noun, in plural unless the count is one
+fn count(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
@@ -0,0 +1,498 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::{FileNotFound, VerificationFailed};
+use crate::utils::{check_path_present, open_file, read_page_header, ParquetFile};
+use clap::Parser;
+use log::debug;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::compression::{create_codec, Codec, CodecOptions};
+use parquet::file::footer::decode_metadata;
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::{ChunkReader, Length};
+use parquet::file::FOOTER_SIZE;
+use parquet::format::{PageHeader, PageType};
+use serde::Serialize;
+use std::path::PathBuf;
+
+static PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// The checks that are run on each file, in the order they are run
+static CHECKS: [&str; 9] = [
+    "magic_start",
+    "magic_end",
+    "footer_length",
+    "metadata",
+    "chunk_bounds",
+    "page_headers",
+    "page_crc",
+    "decompression",
+    "row_counts",
+];
+
+/// Checks the integrity of Parquet file(s)
+#[derive(Parser, Debug)]
+pub struct VerifyCommandArgs {
+    /// Print a JSON report for each file
+    #[arg(short, long)]
+    json: bool,
+
+    /// Parquet files to verify, use - to read from stdin
+    files: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Passed,
+    Failed,
+    /// Nothing could be checked, e.g. no page has a checksum or the footer is broken
+    Skipped,
+}
+
+/// A problem found by a check and where in the file it was found
+#[derive(Serialize, Debug, Default)]
+struct Failure {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    row_group: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    message: String,
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(row_group) = self.row_group {
+            write!(f, "row group {}, ", row_group)?;
+        }
+        if let Some(column) = &self.column {
+            write!(f, "column {}, ", column)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, "offset {}, ", offset)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    /// The number of items checked, e.g. column chunks or pages
+    checked: usize,
+    failures: Vec<Failure>,
+}
+
+impl Check {
+    fn pass(&mut self) {
+        self.checked += 1;
+        if self.status == Status::Skipped {
+            self.status = Status::Passed;
+        }
+    }
+
+    fn fail(&mut self, failure: Failure) {
+        self.checked += 1;
+        self.status = Status::Failed;
+        self.failures.push(failure);
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    file: String,
+    valid: bool,
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn new(file: &ParquetFile) -> Report {
+        let checks = CHECKS
+            .iter()
+            .map(|name| Check {
+                name,
+                status: Status::Skipped,
+                checked: 0,
+                failures: vec![],
+            })
+            .collect();
+        Report {
+            file: file.path().display().to_string(),
+            valid: true,
+            checks,
+        }
+    }
+
+    fn check(&mut self, name: &str) -> &mut Check {
+        self.checks
+            .iter_mut()
+            .find(|check| check.name == name)
+            .expect("checks are created for every name")
+    }
+
+    fn finish(mut self) -> Report {
+        self.valid = self.checks.iter().all(|c| c.status != Status::Failed);
+        self
+    }
+}
+
+pub(crate) fn execute(opts: VerifyCommandArgs) -> Result<(), PQRSError> {
+    debug!("The file names to verify are: {:?}", opts.files);
+
+    // make sure all files are present before verifying any of them
+    for file_name in &opts.files {
+        if !check_path_present(file_name) {
+            return Err(FileNotFound(file_name.to_path_buf()));
+        }
+    }
+
+    let mut failed = 0;
+    for file_name in &opts.files {
+        let file = open_file(file_name)?;
+        let report = verify_file(&file).map_err(|e| e.in_file(file_name))?;
+        if !report.valid {
+            failed += 1;
+        }
+
+        if opts.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            print_report(&report);
+        }
+    }
+
+    if failed > 0 {
+        return Err(VerificationFailed(failed, opts.files.len()));
+    }
+
+    Ok(())
+}
+
+fn print_report(report: &Report) {
+    let verdict = if report.valid { "valid" } else { "invalid" };
+    println!("File: {}: {}", report.file, verdict);
+    for check in &report.checks {
+        let status = match check.status {
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Skipped => "skipped",
+        };
+        println!("  {}: {} ({} checked)", check.name, status, check.checked);
+        for failure in &check.failures {
+            println!("    {}", failure);
+        }
+    }
+}
+
+/// Run all the checks on the file. Checks that depend on a part of the file that is
+/// broken are skipped, e.g. no column chunk is checked when the footer is corrupt.
+fn verify_file(file: &ParquetFile) -> Result<Report, PQRSError> {
+    let mut report = Report::new(file);
+    let file_size = file.len();
+    if file_size < (PARQUET_MAGIC.len() + FOOTER_SIZE) as u64 {
+        report.check("footer_length").fail(Failure {
+            message: format!("File of {} bytes is too small to be Parquet", file_size),
+            ..Default::default()
+        });
+        return Ok(report.finish());
+    }
+
+    let start = file.get_bytes(0, PARQUET_MAGIC.len())?;
+    if start.as_ref() == PARQUET_MAGIC {
+        report.check("magic_start").pass();
+    } else {
+        report.check("magic_start").fail(Failure {
+            offset: Some(0),
+            message: format!(
+                "Expected the magic bytes PAR1 but found {:02x?}",
+                &start[..]
+            ),
+            ..Default::default()
+        });
+    }
+
+    // the footer is the length of the metadata followed by the magic bytes
+    let footer_offset = file_size - FOOTER_SIZE as u64;
+    let footer = file.get_bytes(footer_offset, FOOTER_SIZE)?;
+    if &footer[4..] != PARQUET_MAGIC {
+        report.check("magic_end").fail(Failure {
+            offset: Some(footer_offset + 4),
+            message: format!(
+                "Expected the magic bytes PAR1 but found {:02x?}",
+                &footer[4..]
+            ),
+            ..Default::default()
+        });
+        return Ok(report.finish());
+    }
+    report.check("magic_end").pass();
+
+    let metadata_len =
+        u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as u64;
+    if metadata_len + (PARQUET_MAGIC.len() + FOOTER_SIZE) as u64 > file_size {
+        report.check("footer_length").fail(Failure {
+            offset: Some(footer_offset),
+            message: format!(
+                "Metadata length {} does not fit in the file of {} bytes",
+                metadata_len, file_size
+            ),
+            ..Default::default()
+        });
+        return Ok(report.finish());
+    }
+    report.check("footer_length").pass();
+
+    let metadata_offset = footer_offset - metadata_len;
+    let metadata_bytes = file.get_bytes(metadata_offset, metadata_len as usize)?;
+    let metadata = match decode_metadata(&metadata_bytes) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            report.check("metadata").fail(Failure {
+                offset: Some(metadata_offset),
+                message: e.to_string(),
+                ..Default::default()
+            });
+            return Ok(report.finish());
+        }
+    };
+    report.check("metadata").pass();
+
+    let mut readable_row_groups = vec![];
+    for (row_group, row_group_metadata) in metadata.row_groups().iter().enumerate() {
+        let mut readable = true;
+        for column in row_group_metadata.columns() {
+            let start = column
+                .dictionary_page_offset()
+                .unwrap_or_else(|| column.data_page_offset());
+            let length = column.compressed_size();
+            // a corrupt footer can hold any value, so check the signs before casting
+            // and the sum without overflowing
+            let in_bounds = start >= PARQUET_MAGIC.len() as i64
+                && length >= 0
+                && matches!(
+                    start.checked_add(length),
+                    Some(end) if end as u64 <= metadata_offset
+                );
+            if !in_bounds {
+                report.check("chunk_bounds").fail(Failure {
+                    row_group: Some(row_group),
+                    column: Some(column.column_path().string()),
+                    offset: None,
+                    message: format!(
+                        "Column chunk of {} bytes at offset {} is outside of the data, \
+                         which ends at offset {}",
+                        length, start, metadata_offset
+                    ),
+                });
+                readable = false;
+                continue;
+            }
+            report.check("chunk_bounds").pass();
+
+            let data = file.get_bytes(start as u64, length as usize)?;
+            let pages = verify_pages(&mut report, row_group, column, start as u64, &data);
+            if let Some(failure) = pages {
+                report.check("page_headers").fail(failure);
+                readable = false;
+            }
+        }
+        if readable {
+            readable_row_groups.push(row_group);
+        }
+    }
+
+    verify_row_counts(&mut report, file, &metadata, &readable_row_groups)?;
+
+    Ok(report.finish())
+}
+
+/// Check the header, checksum and compression of every page of the column chunk.
+/// Returns the failure that stopped the pages from being read, as the position of the
+/// next page is not known after a page header cannot be read.
+fn verify_pages(
+    report: &mut Report,
+    row_group: usize,
+    column: &ColumnChunkMetaData,
+    start: u64,
+    data: &[u8],
+) -> Option<Failure> {
+    let failure = |offset: u64, message: String| Failure {
+        row_group: Some(row_group),
+        column: Some(column.column_path().string()),
+        offset: Some(offset),
+        message,
+    };
+    let mut codec = match create_codec(column.compression(), &CodecOptions::default()) {
+        Ok(codec) => codec,
+        Err(e) => return Some(failure(start, e.to_string())),
+    };
+
+    let mut position = 0;
+    while position < data.len() {
+        let offset = start + position as u64;
+        let (header, header_len) = match read_page_header(&data[position..]) {
+            Ok(header) => header,
+            Err(e) => {
+                let message = format!("Could not decode page header: {}", e);
+                return Some(failure(offset, message));
+            }
+        };
+
+        let page_start = position + header_len;
+        let page_end = page_start + header.compressed_page_size.max(0) as usize;
+        if header.compressed_page_size < 0 || page_end > data.len() {
+            return Some(failure(
+                offset,
+                format!(
+                    "Page of {} bytes runs past the end of the column chunk",
+                    header.compressed_page_size
+                ),
+            ));
+        }
+        report.check("page_headers").pass();
+
+        let page = &data[page_start..page_end];
+        if let Some(crc) = header.crc {
+            let actual = crc32fast::hash(page);
+            if actual == crc as u32 {
+                report.check("page_crc").pass();
+            } else {
+                report.check("page_crc").fail(failure(
+                    offset,
+                    format!(
+                        "Page checksum is {:08x} but the page data has checksum {:08x}",
+                        crc as u32, actual
+                    ),
+                ));
+            }
+        }
+
+        if let Err(message) = decompress_page(&mut codec, &header, page) {
+            report.check("decompression").fail(failure(offset, message));
+        } else if codec.is_some() {
+            report.check("decompression").pass();
+        }
+
+        position = page_end;
+    }
+
+    None
+}
+
+/// Decompress the page and check that it has the uncompressed size in its header
+fn decompress_page(
+    codec: &mut Option<Box<dyn Codec>>,
+    header: &PageHeader,
+    page: &[u8],
+) -> Result<(), String> {
+    let codec = match codec {
+        Some(codec) => codec,
+        None => return Ok(()),
+    };
+
+    // the levels of v2 data pages are never compressed, only the values may be
+    let (compressed, uncompressed_size) = match header.type_ {
+        PageType::DATA_PAGE | PageType::DICTIONARY_PAGE => {
+            (page, header.uncompressed_page_size)
+        }
+        PageType::DATA_PAGE_V2 => match &header.data_page_header_v2 {
+            Some(v2) if v2.is_compressed != Some(false) => {
+                let levels_len = (v2.repetition_levels_byte_length
+                    + v2.definition_levels_byte_length)
+                    .max(0) as usize;
+                if levels_len > page.len() {
+                    return Err(format!(
+                        "Levels of {} bytes are larger than the page of {} bytes",
+                        levels_len,
+                        page.len()
+                    ));
+                }
+                (
+                    &page[levels_len..],
+                    header.uncompressed_page_size - levels_len as i32,
+                )
+            }
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    let mut decompressed = Vec::new();
+    let size = codec
+        .decompress(
+            compressed,
+            &mut decompressed,
+            Some(uncompressed_size.max(0) as usize),
+        )
+        .map_err(|e| format!("Could not decompress page: {}", e))?;
+    if size != uncompressed_size as usize {
+        return Err(format!(
+            "Page decompressed to {} bytes but its header says {}",
+            size, uncompressed_size
+        ));
+    }
+    Ok(())
+}
+
+/// Decode every row group whose column chunks could be read, and compare the number
+/// of rows decoded with the number of rows in the metadata
+fn verify_row_counts(
+    report: &mut Report,
+    file: &ParquetFile,
+    metadata: &ParquetMetaData,
+    row_groups: &[usize],
+) -> Result<(), PQRSError> {
+    if row_groups.is_empty() {
+        return Ok(());
+    }
+
+    let arrow_metadata =
+        match ArrowReaderMetadata::load(file, ArrowReaderOptions::default()) {
+            Ok(arrow_metadata) => arrow_metadata,
+            Err(e) => {
+                report.check("row_counts").fail(Failure {
+                    message: format!("Could not read the schema: {}", e),
+                    ..Default::default()
+                });
+                return Ok(());
+            }
+        };
+
+    for row_group in row_groups {
+        let expected = metadata.row_group(*row_group).num_rows();
+        let decoded = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            file.try_clone()?,
+            arrow_metadata.clone(),
+        )
+        .with_row_groups(vec![*row_group])
+        .build()
+        .map_err(|e| e.to_string())
+        .and_then(|mut batch_reader| {
+            batch_reader.try_fold(0, |rows, batch| match batch {
+                Ok(batch) => Ok(rows + batch.num_rows() as i64),
+                Err(e) => Err(e.to_string()),
+            })
+        });
+
+        let failure = |message: String| Failure {
+            row_group: Some(*row_group),
+            message,
+            ..Default::default()
+        };
+        match decoded {
+            Ok(rows) if rows == expected => report.check("row_counts").pass(),
+            Ok(rows) => report.check("row_counts").fail(failure(format!(
+                "Decoded {} rows but the metadata has {}",
+                rows, expected
+            ))),
+            Err(e) => report
+                .check("row_counts")
+                .fail(failure(format!("Could not decode the row group: {}", e))),
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,13 @@
 use arrow::error::ArrowError;
+use object_store::Error as ObjectStoreError;
 use parquet::errors::ParquetError;
+use rayon::ThreadPoolBuildError;
 use serde_json::Error as SerdeJsonError;
+use std::error::Error as StdError;
 use std::io;
 use std::io::{BufWriter, IntoInnerError};
 use std::num::ParseIntError;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
@@ -33,4 +36,198 @@ pub enum PQRSError {
     UTF8ConvertError(#[from] FromUtf8Error),
     #[error("Could not read/write to buffer")]
     BufferWriteError(#[from] IntoInnerError<BufWriter<Vec<u8>>>),
+    #[error("Invalid rendering option: {0}")]
+    InvalidRenderOption(String),
+    #[error("Invalid location: {0}")]
+    InvalidLocation(String),
+    #[error("Could not read from object store")]
+    ObjectStoreError(#[from] ObjectStoreError),
+    #[error("Could not write CSV")]
+    CsvError(#[from] csv::Error),
+    #[error("Could not start worker threads")]
+    ThreadPoolError(#[from] ThreadPoolBuildError),
+    #[error(
+        "Could not read {}, row group {row_group}{}",
+        .file.display(),
+        column_context(.column)
+    )]
+    CorruptRowGroup {
+        file: PathBuf,
+        row_group: usize,
+        /// The first column that could not be decoded, if it could be found
+        column: Option<String>,
+        /// The number of rows of the row group that were not read
+        rows_skipped: i64,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error("{0} of {1} files failed verification")]
+    VerificationFailed(usize, usize),
+    #[error(
+        "Could not read a schema from {}, expected a Parquet file, the output of \
+         schema --json or schema --arrow, or a Parquet message type",
+        .0.display()
+    )]
+    InvalidSchema(PathBuf),
+    #[error("No row groups could be recovered from {}", .0.display())]
+    NothingRecovered(PathBuf),
+    #[error("Key column {0} is not a column of both files")]
+    UnknownKeyColumn(String),
+    #[error("Column {0} is not a column of the file")]
+    UnknownColumn(String),
+    #[error("Cannot probe column {column}: {reason}")]
+    InvalidProbe { column: String, reason: String },
+    #[error("{0} schemas are not {1} compatible with the schema before them")]
+    IncompatibleSchemas(usize, String),
+    #[error("Invalid contract {}: {1}", .0.display())]
+    InvalidContract(PathBuf, String),
+    #[error("{0} of {1} files failed validation")]
+    ValidationFailed(usize, usize),
+    #[error("The files differ in {0}")]
+    FilesDiffer(String),
+    #[error("Column {column} has type {data_type}, which has no equivalent in {format}")]
+    UnsupportedType {
+        column: String,
+        data_type: String,
+        format: String,
+    },
+    #[error("{source}")]
+    InFile {
+        file: PathBuf,
+        source: Box<PQRSError>,
+    },
+}
+
+/// The kind of failure, each of which exits the process with its own code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// An unexpected internal failure
+    Internal,
+    /// An invalid option or argument, the same code is used for command line errors
+    Usage,
+    /// An input file that does not exist
+    FileNotFound,
+    /// Reading or writing a local file or stdio failed
+    Io,
+    /// The file is not valid Parquet or its data could not be decoded
+    InvalidData,
+    /// A request to a remote object store failed
+    Remote,
+    /// The files were read, but a check of them found a problem
+    CheckFailed,
+}
+
+impl ErrorCategory {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCategory::Internal => 1,
+            ErrorCategory::Usage => 2,
+            ErrorCategory::FileNotFound => 3,
+            ErrorCategory::Io => 4,
+            ErrorCategory::InvalidData => 5,
+            ErrorCategory::Remote => 6,
+            ErrorCategory::CheckFailed => 7,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCategory::Internal => "internal",
+            ErrorCategory::Usage => "usage",
+            ErrorCategory::FileNotFound => "file_not_found",
+            ErrorCategory::Io => "io",
+            ErrorCategory::InvalidData => "invalid_data",
+            ErrorCategory::Remote => "remote",
+            ErrorCategory::CheckFailed => "check_failed",
+        }
+    }
+}
+
+impl PQRSError {
+    /// Attach the file that was being processed, unless the error already names one
+    pub fn in_file<P: AsRef<Path>>(self, file: P) -> PQRSError {
+        if self.file().is_some() {
+            return self;
+        }
+        PQRSError::InFile {
+            file: file.as_ref().to_path_buf(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The file the error happened in, if it is known
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            PQRSError::FileNotFound(file)
+            | PQRSError::CouldNotOpenFile(file)
+            | PQRSError::FileExists(file)
+            | PQRSError::InvalidSchema(file)
+            | PQRSError::NothingRecovered(file)
+            | PQRSError::InvalidContract(file, _)
+            | PQRSError::CorruptRowGroup { file, .. }
+            | PQRSError::InFile { file, .. } => Some(file),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            PQRSError::FileNotFound(_) | PQRSError::CouldNotOpenFile(_) => {
+                ErrorCategory::FileNotFound
+            }
+            PQRSError::FileExists(_)
+            | PQRSError::UnableToProcessFile(_)
+            | PQRSError::BufferWriteError(_)
+            | PQRSError::CsvError(_) => ErrorCategory::Io,
+            PQRSError::ParquetError(_)
+            | PQRSError::ArrowReadWriteError(_)
+            | PQRSError::UTF8ConvertError(_)
+            | PQRSError::CorruptRowGroup { .. }
+            | PQRSError::InvalidSchema(_)
+            | PQRSError::NothingRecovered(_) => ErrorCategory::InvalidData,
+            PQRSError::VerificationFailed(..)
+            | PQRSError::IncompatibleSchemas(..)
+            | PQRSError::ValidationFailed(..)
+            | PQRSError::FilesDiffer(_) => ErrorCategory::CheckFailed,
+            PQRSError::UnableToReadNumber(_)
+            | PQRSError::UnsupportedOperation()
+            | PQRSError::InvalidRenderOption(_)
+            | PQRSError::InvalidLocation(_)
+            | PQRSError::UnknownKeyColumn(_)
+            | PQRSError::UnknownColumn(_)
+            | PQRSError::InvalidProbe { .. }
+            | PQRSError::InvalidContract(..)
+            | PQRSError::UnsupportedType { .. } => ErrorCategory::Usage,
+            PQRSError::ObjectStoreError(_) => ErrorCategory::Remote,
+            PQRSError::SerdeJsonError(_) | PQRSError::ThreadPoolError(_) => {
+                ErrorCategory::Internal
+            }
+            PQRSError::InFile { source, .. } => source.category(),
+        }
+    }
+
+    /// The message of this error followed by the messages of the errors that caused it.
+    /// A cause whose message is already part of the previous one is left out.
+    pub fn messages(&self) -> Vec<String> {
+        let mut messages: Vec<String> = vec![];
+        let mut next: Option<&(dyn StdError + 'static)> = match self {
+            PQRSError::InFile { source, .. } => Some(source.as_ref()),
+            error => Some(error),
+        };
+        while let Some(error) = next {
+            let message = error.to_string();
+            let repeated = messages.last().is_some_and(|last| last.ends_with(&message));
+            if !repeated && !message.is_empty() {
+                messages.push(message);
+            }
+            next = error.source();
+        }
+        messages
+    }
+}
+
+fn column_context(column: &Option<String>) -> String {
+    match column {
+        Some(column) => format!(", column {}", column),
+        None => String::new(),
+    }
 }
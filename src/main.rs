@@ -1,21 +1,46 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use std::io::Write;
 
-use crate::errors::PQRSError;
+use crate::errors::{ErrorCategory, PQRSError};
 
 mod commands;
 mod errors;
+mod progress;
+mod remote;
+mod render;
+mod schema_formats;
 mod utils;
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    Bloom(commands::bloom::BloomCommandArgs),
     Cat(commands::cat::CatCommandArgs),
+    Diff(commands::diff::DiffCommandArgs),
+    Dictionary(commands::dictionary::DictionaryCommandArgs),
     Head(commands::head::HeadCommandArgs),
+    Layout(commands::layout::LayoutCommandArgs),
     Merge(commands::merge::MergeCommandArgs),
+    PageIndex(commands::page_index::PageIndexCommandArgs),
+    Pages(commands::pages::PagesCommandArgs),
+    Recover(commands::recover::RecoverCommandArgs),
     #[command(alias = "rowcount")]
     RowCount(commands::rowcount::RowCountCommandArgs),
+    #[command(alias = "rowgroups")]
+    RowGroups(commands::rowgroups::RowGroupsCommandArgs),
     Sample(commands::sample::SampleCommandArgs),
     Schema(commands::schema::SchemaCommandArgs),
+    SchemaDiff(commands::schema_diff::SchemaDiffCommandArgs),
     Size(commands::size::SizeCommandArgs),
+    Validate(commands::validate::ValidateCommandArgs),
+    Verify(commands::verify::VerifyCommandArgs),
+}
+
+/// How errors are printed on stderr
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    Text,
+    Json,
 }
 
 #[derive(Parser, Debug)]
@@ -25,12 +50,25 @@ struct Args {
     #[arg(short, long)]
     debug: bool,
 
+    /// Print errors as text or as a single line of JSON
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Text, global = true)]
+    error_format: ErrorFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
-fn main() -> Result<(), PQRSError> {
-    let args = Args::parse();
+fn main() {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(error) => {
+            if error.use_stderr() && requested_error_format() == ErrorFormat::Json {
+                report_usage_error(&error);
+                std::process::exit(ErrorCategory::Usage.exit_code());
+            }
+            error.exit()
+        }
+    };
 
     if args.debug {
         std::env::set_var("RUST_LOG", "debug");
@@ -39,15 +77,108 @@ fn main() -> Result<(), PQRSError> {
 
     log::debug!("args: {:?}", args);
 
-    match args.command {
+    if let Err(error) = run(args.command) {
+        // the process exits without running destructors, flush what was printed so far
+        let _ = std::io::stdout().flush();
+        report_error(&error, args.error_format);
+        std::process::exit(error.category().exit_code());
+    }
+}
+
+fn run(command: Commands) -> Result<(), PQRSError> {
+    match command {
+        Commands::Bloom(opts) => commands::bloom::execute(opts)?,
         Commands::Cat(opts) => commands::cat::execute(opts)?,
+        Commands::Diff(opts) => commands::diff::execute(opts)?,
+        Commands::Dictionary(opts) => commands::dictionary::execute(opts)?,
         Commands::Head(opts) => commands::head::execute(opts)?,
+        Commands::Layout(opts) => commands::layout::execute(opts)?,
         Commands::Merge(opts) => commands::merge::execute(opts)?,
+        Commands::PageIndex(opts) => commands::page_index::execute(opts)?,
+        Commands::Pages(opts) => commands::pages::execute(opts)?,
+        Commands::Recover(opts) => commands::recover::execute(opts)?,
         Commands::RowCount(opts) => commands::rowcount::execute(opts)?,
+        Commands::RowGroups(opts) => commands::rowgroups::execute(opts)?,
         Commands::Sample(opts) => commands::sample::execute(opts)?,
         Commands::Schema(opts) => commands::schema::execute(opts)?,
+        Commands::SchemaDiff(opts) => commands::schema_diff::execute(opts)?,
         Commands::Size(opts) => commands::size::execute(opts)?,
+        Commands::Validate(opts) => commands::validate::execute(opts)?,
+        Commands::Verify(opts) => commands::verify::execute(opts)?,
     }
 
     Ok(())
 }
+
+/// Print the error with the chain of errors that caused it on stderr
+fn report_error(error: &PQRSError, format: ErrorFormat) {
+    let category = error.category();
+    let messages = error.messages();
+    let (message, causes) = messages.split_first().expect("errors have a message");
+
+    match format {
+        ErrorFormat::Text => {
+            match error {
+                PQRSError::InFile { file, .. } => {
+                    eprintln!("Error: {}: {}", file.display(), message)
+                }
+                _ => eprintln!("Error: {}", message),
+            }
+            for cause in causes {
+                eprintln!("  Caused by: {}", cause);
+            }
+        }
+        ErrorFormat::Json => print_json_error(
+            category,
+            message,
+            error.file().map(|file| file.display().to_string()),
+            causes,
+        ),
+    }
+}
+
+/// The error format asked for on the command line, for errors in the command line
+/// itself that happen before the arguments could be parsed
+fn requested_error_format() -> ErrorFormat {
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = args
+        .iter()
+        .enumerate()
+        .find_map(|(i, arg)| match arg.as_str() {
+            "--error-format" => args.get(i + 1).map(String::as_str),
+            _ => arg.strip_prefix("--error-format="),
+        });
+    value
+        .and_then(|value| ErrorFormat::from_str(value, true).ok())
+        .unwrap_or(ErrorFormat::Text)
+}
+
+/// Print an invalid command line as a usage error, with the first line of the
+/// message clap would print
+fn report_usage_error(error: &clap::Error) {
+    let rendered = error.render().to_string();
+    let message = rendered
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches("error: ");
+    print_json_error(ErrorCategory::Usage, message, None, &[]);
+}
+
+fn print_json_error(
+    category: ErrorCategory,
+    message: &str,
+    file: Option<String>,
+    causes: &[String],
+) {
+    let report = json!({
+        "error": {
+            "category": category.name(),
+            "exit_code": category.exit_code(),
+            "message": message,
+            "file": file,
+            "causes": causes,
+        }
+    });
+    eprintln!("{}", report);
+}
@@ -0,0 +1,94 @@
+use crate::errors::PQRSError;
+use crate::utils::{get_pretty_size, read_metadata, FooterArgs, ParquetFile};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{stderr, IsTerminal};
+
+static PROGRESS_TEMPLATE: &str =
+    "[{elapsed_precise}] {bar:30} {human_pos}/{human_len} rows, {msg}, ETA {eta}";
+
+/// A progress indicator on stderr for commands that read all the rows of their inputs.
+/// The total number of rows and row groups is taken from the footers of the files,
+/// and the indicator is hidden when it is disabled or stderr is not a terminal.
+pub struct Progress {
+    bar: ProgressBar,
+    /// The row count at the end of each row group, across all the files added
+    row_group_ends: Vec<u64>,
+    completed_row_groups: usize,
+    /// Handles to the files added, used to report the bytes read so far
+    files: Vec<ParquetFile>,
+}
+
+impl Progress {
+    pub fn new(enabled: bool) -> Progress {
+        let bar = if enabled && stderr().is_terminal() {
+            let bar =
+                ProgressBar::with_draw_target(Some(0), ProgressDrawTarget::stderr());
+            bar.set_style(
+                ProgressStyle::with_template(PROGRESS_TEMPLATE)
+                    .expect("the progress template is valid"),
+            );
+            bar
+        } else {
+            ProgressBar::hidden()
+        };
+
+        let progress = Progress {
+            bar,
+            row_group_ends: vec![],
+            completed_row_groups: 0,
+            files: vec![],
+        };
+        progress.update_message();
+        progress
+    }
+
+    /// Add the rows of the given file to the total using the row counts in its footer
+    pub fn add_file(&mut self, file: &ParquetFile) -> Result<(), PQRSError> {
+        if self.bar.is_hidden() {
+            return Ok(());
+        }
+
+        let metadata = read_metadata(file, &FooterArgs::default())?;
+        let mut total = self.row_group_ends.last().copied().unwrap_or(0);
+        for row_group in metadata.row_groups() {
+            total += row_group.num_rows() as u64;
+            self.row_group_ends.push(total);
+        }
+        self.files.push(file.try_clone()?);
+        self.bar.set_length(total);
+        self.update_message();
+
+        Ok(())
+    }
+
+    /// Record that the given number of rows has been processed
+    pub fn inc(&mut self, rows: u64) {
+        if self.bar.is_hidden() {
+            return;
+        }
+
+        self.bar.inc(rows);
+        let position = self.bar.position();
+        while self.completed_row_groups < self.row_group_ends.len()
+            && self.row_group_ends[self.completed_row_groups] <= position
+        {
+            self.completed_row_groups += 1;
+        }
+        self.update_message();
+    }
+
+    /// Remove the indicator from the terminal once all the rows are processed
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    fn update_message(&self) {
+        let bytes_read: u64 = self.files.iter().map(|f| f.stats().bytes_read()).sum();
+        self.bar.set_message(format!(
+            "{}/{} row groups, {} read",
+            self.completed_row_groups,
+            self.row_group_ends.len(),
+            get_pretty_size(bytes_read as i64, false)
+        ));
+    }
+}
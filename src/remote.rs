@@ -0,0 +1,174 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::InvalidLocation;
+use bytes::{Buf, Bytes};
+use log::debug;
+use object_store::path::Path as ObjectPath;
+use object_store::{parse_url_opts, ObjectStore};
+use parquet::errors::ParquetError;
+use parquet::file::reader::{ChunkReader, Length};
+use std::cmp::min;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// URL schemes that are read from an object store instead of the local filesystem
+static REMOTE_SCHEMES: [&str; 8] = [
+    "s3://", "s3a://", "gs://", "az://", "abfs://", "abfss://", "http://", "https://",
+];
+
+/// Prefixes of the environment variables that configure the object stores, the same
+/// ones the store builders read in `from_env`
+static ENV_PREFIXES: [&str; 3] = ["AWS_", "GOOGLE_", "AZURE_"];
+
+/// The number of bytes fetched by the first request when parquet streams through a
+/// file, e.g. to read a page header. Each subsequent request doubles in size.
+static INITIAL_BLOCK_SIZE: usize = 64 * 1024;
+static MAX_BLOCK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Check if the given location refers to a file in a remote object store
+pub fn is_remote<P: AsRef<Path>>(location: P) -> bool {
+    location
+        .as_ref()
+        .to_str()
+        .map(|s| REMOTE_SCHEMES.iter().any(|scheme| s.starts_with(scheme)))
+        .unwrap_or(false)
+}
+
+/// The runtime used to drive the async object store clients
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .expect("could not start the object store runtime")
+    })
+}
+
+/// The remote files opened so far by location, so that checking that a file exists
+/// and opening it later only looks the object up once
+fn opened() -> &'static Mutex<HashMap<String, RemoteFile>> {
+    static OPENED: OnceLock<Mutex<HashMap<String, RemoteFile>>> = OnceLock::new();
+    OPENED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// A parquet file in an object store that is read using ranged requests,
+/// so that only the footer and the column chunks that are needed are transferred
+#[derive(Clone)]
+pub struct RemoteFile {
+    store: Arc<dyn ObjectStore>,
+    path: ObjectPath,
+    size: u64,
+}
+
+impl std::fmt::Debug for RemoteFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "RemoteFile({}, {} bytes)", self.path, self.size)
+    }
+}
+
+impl RemoteFile {
+    /// Connect to the object store for the given location and look up the object size.
+    /// Credentials and options are taken from the `AWS_`, `GOOGLE_` and `AZURE_`
+    /// environment variables, e.g. `AWS_REGION`
+    pub fn open<P: AsRef<Path>>(location: P) -> Result<RemoteFile, PQRSError> {
+        let location = location.as_ref().to_string_lossy();
+        if let Some(file) = opened().lock().unwrap().get(location.as_ref()) {
+            return Ok(file.clone());
+        }
+        let url = Url::parse(&location).map_err(|e| InvalidLocation(e.to_string()))?;
+
+        let mut options: Vec<(String, String)> = std::env::vars()
+            .filter(|(key, _)| ENV_PREFIXES.iter().any(|prefix| key.starts_with(prefix)))
+            .map(|(key, value)| (key.to_ascii_lowercase(), value))
+            .collect();
+        if url.scheme() == "http" {
+            options.push((String::from("allow_http"), String::from("true")));
+        }
+
+        let (store, path) = parse_url_opts(&url, options)?;
+        let store: Arc<dyn ObjectStore> = Arc::from(store);
+        let meta = runtime().block_on(store.head(&path))?;
+        debug!("Found remote object {} with {} bytes", path, meta.size);
+
+        let file = RemoteFile {
+            store,
+            path,
+            size: meta.size as u64,
+        };
+        opened()
+            .lock()
+            .unwrap()
+            .insert(location.into_owned(), file.clone());
+        Ok(file)
+    }
+
+    fn fetch(&self, start: u64, length: usize) -> Result<Bytes, object_store::Error> {
+        let start = start as usize;
+        debug!(
+            "Fetching bytes {}..{} of {}",
+            start,
+            start + length,
+            self.path
+        );
+        runtime().block_on(self.store.get_range(&self.path, start..start + length))
+    }
+}
+
+impl Length for RemoteFile {
+    fn len(&self) -> u64 {
+        self.size
+    }
+}
+
+impl ChunkReader for RemoteFile {
+    type T = RemoteReader;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        Ok(RemoteReader {
+            file: self.clone(),
+            position: start,
+            block_size: INITIAL_BLOCK_SIZE,
+            buffer: Bytes::new(),
+        })
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        self.fetch(start, length)
+            .map_err(|e| ParquetError::External(Box::new(e)))
+    }
+}
+
+/// A reader that streams through a remote file one block at a time
+pub struct RemoteReader {
+    file: RemoteFile,
+    position: u64,
+    block_size: usize,
+    buffer: Bytes,
+}
+
+impl Read for RemoteReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.buffer.is_empty() {
+            let remaining = self.file.size.saturating_sub(self.position) as usize;
+            if remaining == 0 {
+                return Ok(0);
+            }
+            let length = min(remaining, self.block_size);
+            self.buffer = self
+                .file
+                .fetch(self.position, length)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            self.position += length as u64;
+            self.block_size = min(self.block_size * 2, MAX_BLOCK_SIZE);
+        }
+
+        let n = min(buf.len(), self.buffer.len());
+        self.buffer.copy_to_slice(&mut buf[..n]);
+        Ok(n)
+    }
+}
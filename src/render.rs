@@ -0,0 +1,556 @@
+use crate::errors::PQRSError;
+use crate::errors::PQRSError::InvalidRenderOption;
+use arrow::array::timezone::Tz;
+use arrow::array::{
+    Array, ArrayRef, AsArray, Int64Array, ListArray, MapArray, RecordBatch, StringArray,
+    StructArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    DataType, Field as ArrowField, Fields, Int64Type, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+};
+use arrow::temporal_conversions::as_datetime;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Args, ValueEnum};
+use parquet::basic::{ConvertedType, LogicalType, Repetition, Type as PhysicalType};
+use parquet::format::TimeUnit as ParquetTimeUnit;
+use parquet::record::{Field, Row};
+use parquet::schema::types::{SchemaDescriptor, Type, TypePtr};
+use serde_json::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Encodings available for printing binary values
+#[derive(ValueEnum, Copy, Clone, Debug)]
+pub enum BinaryFormat {
+    Hex,
+    Base64,
+    /// Interpret the bytes as UTF-8, replacing invalid sequences
+    Utf8,
+}
+
+/// Units available for printing timestamps as integers since the unix epoch
+#[derive(ValueEnum, Copy, Clone, Debug)]
+pub enum EpochUnit {
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+/// Options controlling how binary, decimal, timestamp and UUID values are printed
+#[derive(Args, Debug, Clone, Default)]
+pub struct RenderArgs {
+    /// Print binary values as hex, base64 or (lossy) utf8 strings
+    #[arg(long, value_enum)]
+    binary: Option<BinaryFormat>,
+
+    /// Convert timestamps to the given timezone, e.g. UTC, Europe/Paris or +05:30
+    #[arg(long)]
+    timezone: Option<String>,
+
+    /// Print timestamps using the given strftime format, e.g. "%Y-%m-%dT%H:%M:%S%.3f"
+    #[arg(long)]
+    timestamp_format: Option<String>,
+
+    /// Print timestamps as integers since the unix epoch in the given unit
+    #[arg(long, value_enum, conflicts_with_all = ["timezone", "timestamp_format"])]
+    epoch_unit: Option<EpochUnit>,
+}
+
+/// Renders values consistently across the record based and arrow based printers.
+///
+/// Decimals are always printed as exact strings, UUID columns in their canonical
+/// hyphenated form, and the remaining options only apply when explicitly requested.
+/// Values are rendered by the type of their column in the schema of the file.
+#[derive(Debug, Clone, Default)]
+pub struct Renderer {
+    binary: Option<BinaryFormat>,
+    timezone: Option<Tz>,
+    timestamp_format: Option<String>,
+    epoch_unit: Option<EpochUnit>,
+    /// The root of the schema of the file being printed
+    schema: Option<TypePtr>,
+}
+
+impl Renderer {
+    /// Check the options and build a renderer from them, before any file is opened
+    pub fn try_new(args: &RenderArgs) -> Result<Renderer, PQRSError> {
+        let timezone =
+            match &args.timezone {
+                Some(tz) => Some(Tz::from_str(tz).map_err(|_| {
+                    InvalidRenderOption(format!("invalid timezone: {}", tz))
+                })?),
+                None => None,
+            };
+
+        if let Some(format) = &args.timestamp_format {
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(InvalidRenderOption(format!(
+                    "invalid timestamp format: {}",
+                    format
+                )));
+            }
+        }
+
+        Ok(Renderer {
+            binary: args.binary,
+            timezone,
+            timestamp_format: args.timestamp_format.clone(),
+            epoch_unit: args.epoch_unit,
+            schema: None,
+        })
+    }
+
+    /// A copy of the renderer for a file with the given schema
+    pub fn with_schema(&self, schema: &SchemaDescriptor) -> Renderer {
+        Renderer {
+            schema: Some(schema.root_schema_ptr()),
+            ..self.clone()
+        }
+    }
+
+    fn renders_timestamps(&self) -> bool {
+        self.timezone.is_some() || self.timestamp_format.is_some()
+    }
+
+    /// Render the row in the json-like format used by default
+    pub fn render_row_text(&self, row: &Row) -> String {
+        self.row_text(self.schema.as_deref(), row)
+    }
+
+    /// Render the row as a json value
+    pub fn render_row_json(&self, row: &Row) -> Value {
+        self.row_json(self.schema.as_deref(), row)
+    }
+
+    fn row_text(&self, group: Option<&Type>, row: &Row) -> String {
+        let fields = row
+            .get_column_iter()
+            .map(|(name, field)| {
+                let field_type = group.and_then(|group| child_type(group, name));
+                format!("{}: {}", name, self.field_text(field_type, field))
+            })
+            .collect::<Vec<_>>();
+        format!("{{{}}}", fields.join(", "))
+    }
+
+    fn row_json(&self, group: Option<&Type>, row: &Row) -> Value {
+        Value::Object(
+            row.get_column_iter()
+                .map(|(name, field)| {
+                    let field_type = group.and_then(|group| child_type(group, name));
+                    (name.to_owned(), self.field_json(field_type, field))
+                })
+                .collect(),
+        )
+    }
+
+    fn field_text(&self, field_type: Option<&Type>, field: &Field) -> String {
+        match field {
+            Field::Bytes(bytes) if is_uuid(field_type, bytes.data()) => {
+                format!("\"{}\"", format_uuid(bytes.data()))
+            }
+            Field::Bytes(bytes) => match self.binary {
+                Some(format) => format!("\"{}\"", format_binary(bytes.data(), format)),
+                None => field.to_string(),
+            },
+            Field::Group(row) => self.row_text(field_type, row),
+            Field::ListInternal(list) => {
+                let element_type = field_type.and_then(element_type);
+                let elements = list
+                    .elements()
+                    .iter()
+                    .map(|element| self.field_text(element_type, element))
+                    .collect::<Vec<_>>();
+                format!("[{}]", elements.join(", "))
+            }
+            Field::MapInternal(map) => {
+                let (key_type, value_type) = entry_types(field_type);
+                let entries = map
+                    .entries()
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{} -> {}",
+                            self.field_text(key_type, key),
+                            self.field_text(value_type, value)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", entries.join(", "))
+            }
+            _ => self
+                .timestamp_json(field_type, field)
+                .map(|v| text_from_json(&v))
+                .unwrap_or_else(|| field.to_string()),
+        }
+    }
+
+    fn field_json(&self, field_type: Option<&Type>, field: &Field) -> Value {
+        match field {
+            Field::Bytes(bytes) if is_uuid(field_type, bytes.data()) => {
+                Value::String(format_uuid(bytes.data()))
+            }
+            Field::Bytes(bytes) => match self.binary {
+                Some(format) => Value::String(format_binary(bytes.data(), format)),
+                None => field.to_json_value(),
+            },
+            Field::Group(row) => self.row_json(field_type, row),
+            Field::ListInternal(list) => {
+                let element_type = field_type.and_then(element_type);
+                Value::Array(
+                    list.elements()
+                        .iter()
+                        .map(|element| self.field_json(element_type, element))
+                        .collect(),
+                )
+            }
+            Field::MapInternal(map) => {
+                let (key_type, value_type) = entry_types(field_type);
+                Value::Object(
+                    map.entries()
+                        .iter()
+                        .map(|(key, value)| {
+                            let key = text_from_json(&self.field_json(key_type, key));
+                            (key, self.field_json(value_type, value))
+                        })
+                        .collect(),
+                )
+            }
+            _ => self
+                .timestamp_json(field_type, field)
+                .unwrap_or_else(|| field.to_json_value()),
+        }
+    }
+
+    /// Render the value of a timestamp column according to the options. Without
+    /// options only nanosecond timestamps are rendered, which the record reader
+    /// returns as plain integers, in the way it prints the other timestamps.
+    fn timestamp_json(&self, field_type: Option<&Type>, field: &Field) -> Option<Value> {
+        let (value, unit) = timestamp_value(field_type?, field)?;
+        if let Some(epoch_unit) = self.epoch_unit {
+            return convert_epoch(value, &unit, epoch_unit).map(Value::from);
+        }
+        if !self.renders_timestamps() {
+            let datetime = match unit {
+                TimeUnit::Nanosecond => as_datetime::<TimestampNanosecondType>(value)?,
+                _ => return None,
+            };
+            let datetime = Utc.from_utc_datetime(&datetime);
+            return Some(Value::String(
+                datetime.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            ));
+        }
+        self.format_timestamp(value, &unit).map(Value::String)
+    }
+
+    fn format_timestamp(&self, value: i64, unit: &TimeUnit) -> Option<String> {
+        let datetime = match unit {
+            TimeUnit::Second => as_datetime::<TimestampSecondType>(value),
+            TimeUnit::Millisecond => as_datetime::<TimestampMillisecondType>(value),
+            TimeUnit::Microsecond => as_datetime::<TimestampMicrosecondType>(value),
+            TimeUnit::Nanosecond => as_datetime::<TimestampNanosecondType>(value),
+        }?;
+        Some(match &self.timezone {
+            Some(tz) => self.format_datetime(tz.from_utc_datetime(&datetime)),
+            None => self.format_datetime(Utc.from_utc_datetime(&datetime)),
+        })
+    }
+
+    fn format_datetime<T: TimeZone>(&self, datetime: DateTime<T>) -> String
+    where
+        T::Offset: Display,
+    {
+        match &self.timestamp_format {
+            Some(format) => datetime.format(format).to_string(),
+            None => datetime.to_rfc3339(),
+        }
+    }
+
+    /// Render the columns of the record batch that the arrow writers cannot print
+    /// or that the options ask to be printed differently
+    pub fn render_batch(&self, batch: RecordBatch) -> Result<RecordBatch, PQRSError> {
+        let schema = batch.schema();
+        let mut fields = Vec::with_capacity(batch.num_columns());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            let field_type = self
+                .schema
+                .as_deref()
+                .and_then(|schema| child_type(schema, field.name()));
+            let (field, column) = self.render_array(field_type, field, column)?;
+            fields.push(field);
+            columns.push(column);
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(arrow::datatypes::Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    fn render_array(
+        &self,
+        field_type: Option<&Type>,
+        field: &ArrowField,
+        array: &ArrayRef,
+    ) -> Result<(ArrowField, ArrayRef), PQRSError> {
+        let rendered: ArrayRef = match array.data_type() {
+            DataType::Binary => {
+                let array = array.as_binary::<i32>();
+                self.render_bytes(field_type, array.iter())
+            }
+            DataType::LargeBinary => {
+                let array = array.as_binary::<i64>();
+                self.render_bytes(field_type, array.iter())
+            }
+            DataType::FixedSizeBinary(_) => {
+                let array = array.as_fixed_size_binary();
+                self.render_bytes(field_type, array.iter())
+            }
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                cast(array, &DataType::Utf8)?
+            }
+            DataType::Timestamp(unit, _)
+                if self.epoch_unit.is_some() || self.renders_timestamps() =>
+            {
+                let values = cast(array, &DataType::Int64)?;
+                let values = values.as_primitive::<Int64Type>();
+                match self.epoch_unit {
+                    Some(epoch_unit) => Arc::new(
+                        values
+                            .iter()
+                            .map(|v| v.and_then(|v| convert_epoch(v, unit, epoch_unit)))
+                            .collect::<Int64Array>(),
+                    ),
+                    None => Arc::new(
+                        values
+                            .iter()
+                            .map(|v| v.and_then(|v| self.format_timestamp(v, unit)))
+                            .collect::<StringArray>(),
+                    ),
+                }
+            }
+            DataType::Struct(children) => {
+                let array = array.as_struct();
+                let mut fields = Vec::with_capacity(children.len());
+                let mut columns = Vec::with_capacity(children.len());
+                for (child, column) in children.iter().zip(array.columns()) {
+                    let child_type =
+                        field_type.and_then(|group| child_type(group, child.name()));
+                    let (child, column) = self.render_array(child_type, child, column)?;
+                    fields.push(child);
+                    columns.push(column);
+                }
+                Arc::new(StructArray::new(
+                    Fields::from(fields),
+                    columns,
+                    array.nulls().cloned(),
+                ))
+            }
+            DataType::List(item) => {
+                let array = array.as_list::<i32>();
+                let item_type = field_type.and_then(element_type);
+                let (item, values) =
+                    self.render_array(item_type, item, array.values())?;
+                Arc::new(ListArray::new(
+                    Arc::new(item),
+                    array.offsets().clone(),
+                    values,
+                    array.nulls().cloned(),
+                ))
+            }
+            DataType::Map(entries, ordered) => {
+                let array = array.as_map();
+                let entries_array: ArrayRef = Arc::new(array.entries().clone());
+                let entries_type = field_type.and_then(key_value_type);
+                let (entries, values) =
+                    self.render_array(entries_type, entries, &entries_array)?;
+                Arc::new(MapArray::new(
+                    Arc::new(entries),
+                    array.offsets().clone(),
+                    values.as_struct().clone(),
+                    array.nulls().cloned(),
+                    *ordered,
+                ))
+            }
+            _ => array.clone(),
+        };
+
+        let field = field.clone().with_data_type(rendered.data_type().clone());
+        Ok((field, rendered))
+    }
+
+    fn render_bytes<'a>(
+        &self,
+        field_type: Option<&Type>,
+        values: impl Iterator<Item = Option<&'a [u8]>>,
+    ) -> ArrayRef {
+        // the arrow json and csv writers cannot print raw bytes, so base64 is used
+        // unless a different encoding was asked for, as done for json records
+        let format = self.binary.unwrap_or(BinaryFormat::Base64);
+        let rendered = values
+            .map(|value| {
+                value.map(|bytes| {
+                    if is_uuid(field_type, bytes) {
+                        format_uuid(bytes)
+                    } else {
+                        format_binary(bytes, format)
+                    }
+                })
+            })
+            .collect::<StringArray>();
+        Arc::new(rendered)
+    }
+}
+
+/// The type of the child of the group with the given name
+fn child_type<'a>(group: &'a Type, name: &str) -> Option<&'a Type> {
+    if !group.is_group() {
+        return None;
+    }
+    group
+        .get_fields()
+        .iter()
+        .find(|field| field.name() == name)
+        .map(|field| field.as_ref())
+}
+
+/// The type of the elements of a list, following the rules of the parquet format for
+/// lists written in the legacy layouts, or of a repeated field that is not a list
+fn element_type(list: &Type) -> Option<&Type> {
+    let info = list.get_basic_info();
+    if !list.is_group() || info.converted_type() != ConvertedType::LIST {
+        return (info.has_repetition() && info.repetition() == Repetition::REPEATED)
+            .then_some(list);
+    }
+    let repeated = list.get_fields().first()?;
+    if repeated.is_primitive()
+        || repeated.get_fields().len() > 1
+        || repeated.name() == "array"
+        || repeated.name().ends_with("_tuple")
+    {
+        return Some(repeated);
+    }
+    repeated.get_fields().first().map(|field| field.as_ref())
+}
+
+/// The repeated group holding the keys and values of a map
+fn key_value_type(map: &Type) -> Option<&Type> {
+    match map.get_basic_info().converted_type() {
+        ConvertedType::MAP | ConvertedType::MAP_KEY_VALUE if map.is_group() => {
+            map.get_fields().first().map(|field| field.as_ref())
+        }
+        _ => None,
+    }
+}
+
+/// The types of the keys and of the values of a map
+fn entry_types(map: Option<&Type>) -> (Option<&Type>, Option<&Type>) {
+    match map
+        .and_then(key_value_type)
+        .filter(|entries| entries.is_group())
+    {
+        Some(entries) => (
+            entries.get_fields().first().map(|field| field.as_ref()),
+            entries.get_fields().get(1).map(|field| field.as_ref()),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Whether the bytes are a value of a column annotated with the UUID logical type
+fn is_uuid(field_type: Option<&Type>, bytes: &[u8]) -> bool {
+    bytes.len() == 16
+        && matches!(
+            field_type.map(|t| t.get_basic_info().logical_type()),
+            Some(Some(LogicalType::Uuid))
+        )
+}
+
+/// The value of a timestamp column and its unit, which comes from the type of the
+/// column. The record reader converts INT96 timestamps to milliseconds.
+fn timestamp_value(field_type: &Type, field: &Field) -> Option<(i64, TimeUnit)> {
+    if !field_type.is_primitive() {
+        return None;
+    }
+    let unit = match field_type.get_basic_info().logical_type() {
+        Some(LogicalType::Timestamp { unit, .. }) => match unit {
+            ParquetTimeUnit::MILLIS(_) => TimeUnit::Millisecond,
+            ParquetTimeUnit::MICROS(_) => TimeUnit::Microsecond,
+            ParquetTimeUnit::NANOS(_) => TimeUnit::Nanosecond,
+        },
+        _ => match field_type.get_basic_info().converted_type() {
+            ConvertedType::TIMESTAMP_MILLIS => TimeUnit::Millisecond,
+            ConvertedType::TIMESTAMP_MICROS => TimeUnit::Microsecond,
+            _ if field_type.get_physical_type() == PhysicalType::INT96 => {
+                TimeUnit::Millisecond
+            }
+            _ => return None,
+        },
+    };
+    match field {
+        Field::TimestampMillis(value)
+        | Field::TimestampMicros(value)
+        | Field::Long(value) => Some((*value, unit)),
+        _ => None,
+    }
+}
+
+/// Print the json value as text, leaving strings unquoted
+fn text_from_json(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_owned(),
+        _ => value.to_string(),
+    }
+}
+
+/// Encode the given bytes using the requested binary format
+pub fn format_binary(bytes: &[u8], format: BinaryFormat) -> String {
+    match format {
+        BinaryFormat::Hex => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        BinaryFormat::Base64 => BASE64_STANDARD.encode(bytes),
+        BinaryFormat::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Format 16 bytes as a canonical, hyphenated UUID
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex = format_binary(bytes, BinaryFormat::Hex);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Convert a timestamp between units, returning None on overflow
+fn convert_epoch(value: i64, unit: &TimeUnit, target: EpochUnit) -> Option<i64> {
+    let from: i64 = match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    };
+    let to: i64 = match target {
+        EpochUnit::S => 1,
+        EpochUnit::Ms => 1_000,
+        EpochUnit::Us => 1_000_000,
+        EpochUnit::Ns => 1_000_000_000,
+    };
+
+    if to >= from {
+        value.checked_mul(to / from)
+    } else {
+        Some(value.div_euclid(from / to))
+    }
+}
@@ -0,0 +1,1151 @@
+use crate::errors::PQRSError;
+use arrow::datatypes::{DataType, Field, Fields, IntervalUnit, Schema, TimeUnit};
+use clap::ValueEnum;
+use parquet::basic::{ConvertedType, LogicalType, TimeUnit as ParquetTimeUnit};
+use parquet::errors::ParquetError;
+use parquet::schema::types::Type;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The systems a schema can be translated for
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaFormat {
+    /// An ANSI SQL CREATE TABLE statement
+    Sql,
+    /// A Spark StructType in JSON
+    Spark,
+    /// A BigQuery table schema in JSON
+    Bigquery,
+    /// An Avro record schema
+    Avro,
+    /// A JSON Schema describing each row as an object
+    JsonSchema,
+    /// A proto3 message definition
+    Protobuf,
+    /// A Hive CREATE EXTERNAL TABLE statement for Parquet files
+    Hive,
+}
+
+impl SchemaFormat {
+    fn name(self) -> &'static str {
+        match self {
+            SchemaFormat::Sql => "SQL",
+            SchemaFormat::Spark => "Spark",
+            SchemaFormat::Bigquery => "BigQuery",
+            SchemaFormat::Avro => "Avro",
+            SchemaFormat::JsonSchema => "JSON Schema",
+            SchemaFormat::Protobuf => "Protobuf",
+            SchemaFormat::Hive => "Hive",
+        }
+    }
+}
+
+/// Translate the Arrow schema of a file into the given format. The file name without
+/// its extensions is used as the name of the table, record or message.
+pub fn render_schema(
+    format: SchemaFormat,
+    file_name: &Path,
+    schema: &Schema,
+) -> Result<String, PQRSError> {
+    let name = table_name(file_name);
+    let translator = Translator { format };
+    let fields = schema.fields();
+    match format {
+        SchemaFormat::Sql => translator.sql_table(&name, fields),
+        SchemaFormat::Hive => translator.hive_table(&name, fields),
+        SchemaFormat::Spark => {
+            let schema = translator.spark_struct("", fields)?;
+            Ok(serde_json::to_string_pretty(&schema)?)
+        }
+        SchemaFormat::Bigquery => {
+            let schema = fields
+                .iter()
+                .map(|field| translator.bigquery_field(field.name(), field))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(serde_json::to_string_pretty(&schema)?)
+        }
+        SchemaFormat::Avro => {
+            let schema = translator.avro_record(&name, "", fields)?;
+            Ok(serde_json::to_string_pretty(&schema)?)
+        }
+        SchemaFormat::JsonSchema => {
+            let mut schema = translator.json_schema_object("", fields)?;
+            schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+            schema["title"] = json!(name);
+            Ok(serde_json::to_string_pretty(&schema)?)
+        }
+        SchemaFormat::Protobuf => translator.proto_file(&name, fields),
+    }
+}
+
+/// The file name up to its first dot, e.g. pems-1 for data/pems-1.snappy.parquet
+fn table_name(file_name: &Path) -> String {
+    let name = file_name
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match name.split('.').next() {
+        Some(stem) if !stem.is_empty() => stem.to_string(),
+        _ => "schema".to_string(),
+    }
+}
+
+/// The type of the values, without a dictionary or run end encoding around them
+fn value_type(data_type: &DataType) -> &DataType {
+    match data_type {
+        DataType::Dictionary(_, values) => value_type(values),
+        DataType::RunEndEncoded(_, values) => value_type(values.data_type()),
+        data_type => data_type,
+    }
+}
+
+/// The field of the items of any kind of list
+fn list_item(data_type: &DataType) -> Option<&Field> {
+    match data_type {
+        DataType::List(item)
+        | DataType::LargeList(item)
+        | DataType::ListView(item)
+        | DataType::LargeListView(item)
+        | DataType::FixedSizeList(item, _) => Some(item),
+        _ => None,
+    }
+}
+
+/// The key and value fields of a map
+fn map_entries(entries: &Field) -> Option<(&Field, &Field)> {
+    match entries.data_type() {
+        DataType::Struct(fields) if fields.len() == 2 => Some((&fields[0], &fields[1])),
+        _ => None,
+    }
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Replace the characters that are not allowed in an identifier with underscores
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+/// The identifier in upper camel case, as used for message names, e.g. ArrayElement
+fn camel_case(name: &str) -> String {
+    let camel_case: String = identifier(name)
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if camel_case.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        camel_case
+    } else {
+        format!("_{}", camel_case)
+    }
+}
+
+fn quote(name: &str, quote: char) -> String {
+    let escaped = name.replace(quote, &format!("{}{}", quote, quote));
+    format!("{}{}{}", quote, escaped, quote)
+}
+
+struct Translator {
+    format: SchemaFormat,
+}
+
+impl Translator {
+    fn unsupported(&self, path: &str, data_type: &DataType) -> PQRSError {
+        PQRSError::UnsupportedType {
+            column: path.to_string(),
+            data_type: data_type.to_string(),
+            format: self.format.name().to_string(),
+        }
+    }
+
+    fn sql_table(&self, name: &str, fields: &Fields) -> Result<String, PQRSError> {
+        let columns = fields
+            .iter()
+            .map(|field| {
+                let data_type = self.sql_type(field.name(), field.data_type())?;
+                let not_null = if field.is_nullable() { "" } else { " NOT NULL" };
+                Ok(format!(
+                    "  {} {}{}",
+                    quote(field.name(), '"'),
+                    data_type,
+                    not_null
+                ))
+            })
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+        Ok(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            quote(name, '"'),
+            columns.join(",\n")
+        ))
+    }
+
+    fn sql_type(&self, path: &str, data_type: &DataType) -> Result<String, PQRSError> {
+        let sql_type = match value_type(data_type) {
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::Int8 | DataType::Int16 | DataType::UInt8 => "SMALLINT".to_string(),
+            DataType::Int32 | DataType::UInt16 => "INTEGER".to_string(),
+            DataType::Int64 | DataType::UInt32 => "BIGINT".to_string(),
+            DataType::UInt64 => "DECIMAL(20, 0)".to_string(),
+            DataType::Float16 | DataType::Float32 => "REAL".to_string(),
+            DataType::Float64 => "DOUBLE PRECISION".to_string(),
+            DataType::Decimal128(precision, scale)
+            | DataType::Decimal256(precision, scale)
+                if *scale >= 0 =>
+            {
+                format!("DECIMAL({}, {})", precision, scale)
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                "VARCHAR".to_string()
+            }
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+                "VARBINARY".to_string()
+            }
+            DataType::FixedSizeBinary(size) => format!("BINARY({})", size),
+            DataType::Date32 | DataType::Date64 => "DATE".to_string(),
+            DataType::Time32(_) | DataType::Time64(_) => "TIME".to_string(),
+            DataType::Timestamp(_, None) => "TIMESTAMP".to_string(),
+            DataType::Timestamp(_, Some(_)) => "TIMESTAMP WITH TIME ZONE".to_string(),
+            DataType::Duration(_) | DataType::Interval(_) => "INTERVAL".to_string(),
+            DataType::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let path = child_path(path, field.name());
+                        let data_type = self.sql_type(&path, field.data_type())?;
+                        Ok(format!("{} {}", quote(field.name(), '"'), data_type))
+                    })
+                    .collect::<Result<Vec<_>, PQRSError>>()?;
+                format!("ROW({})", fields.join(", "))
+            }
+            DataType::Map(entries, _) => {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, data_type))?;
+                format!(
+                    "MAP({}, {})",
+                    self.sql_type(&child_path(path, key.name()), key.data_type())?,
+                    self.sql_type(&child_path(path, value.name()), value.data_type())?
+                )
+            }
+            value_type => match list_item(value_type) {
+                Some(item) => {
+                    let path = child_path(path, item.name());
+                    format!("{} ARRAY", self.sql_type(&path, item.data_type())?)
+                }
+                None => return Err(self.unsupported(path, data_type)),
+            },
+        };
+        Ok(sql_type)
+    }
+
+    fn hive_table(&self, name: &str, fields: &Fields) -> Result<String, PQRSError> {
+        let columns = fields
+            .iter()
+            .map(|field| {
+                let data_type = self.hive_type(field.name(), field.data_type())?;
+                Ok(format!("  {} {}", quote(field.name(), '`'), data_type))
+            })
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+        Ok(format!(
+            "CREATE EXTERNAL TABLE {} (\n{}\n)\nSTORED AS PARQUET;",
+            quote(name, '`'),
+            columns.join(",\n")
+        ))
+    }
+
+    fn hive_type(&self, path: &str, data_type: &DataType) -> Result<String, PQRSError> {
+        let hive_type = match value_type(data_type) {
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::Int8 => "TINYINT".to_string(),
+            DataType::Int16 | DataType::UInt8 => "SMALLINT".to_string(),
+            DataType::Int32 | DataType::UInt16 => "INT".to_string(),
+            DataType::Int64 | DataType::UInt32 => "BIGINT".to_string(),
+            DataType::UInt64 => "DECIMAL(20,0)".to_string(),
+            DataType::Float16 | DataType::Float32 => "FLOAT".to_string(),
+            DataType::Float64 => "DOUBLE".to_string(),
+            DataType::Decimal128(precision, scale)
+            | DataType::Decimal256(precision, scale)
+                if *precision <= 38 && *scale >= 0 =>
+            {
+                format!("DECIMAL({},{})", precision, scale)
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                "STRING".to_string()
+            }
+            DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_) => "BINARY".to_string(),
+            DataType::Date32 | DataType::Date64 => "DATE".to_string(),
+            DataType::Timestamp(_, _) => "TIMESTAMP".to_string(),
+            DataType::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let path = child_path(path, field.name());
+                        let data_type = self.hive_type(&path, field.data_type())?;
+                        Ok(format!("{}:{}", quote(field.name(), '`'), data_type))
+                    })
+                    .collect::<Result<Vec<_>, PQRSError>>()?;
+                format!("STRUCT<{}>", fields.join(","))
+            }
+            DataType::Map(entries, _) => {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, data_type))?;
+                format!(
+                    "MAP<{},{}>",
+                    self.hive_type(&child_path(path, key.name()), key.data_type())?,
+                    self.hive_type(&child_path(path, value.name()), value.data_type())?
+                )
+            }
+            value_type => match list_item(value_type) {
+                Some(item) => {
+                    let path = child_path(path, item.name());
+                    format!("ARRAY<{}>", self.hive_type(&path, item.data_type())?)
+                }
+                None => return Err(self.unsupported(path, data_type)),
+            },
+        };
+        Ok(hive_type)
+    }
+
+    fn spark_struct(&self, path: &str, fields: &Fields) -> Result<Value, PQRSError> {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let path = child_path(path, field.name());
+                Ok(json!({
+                    "name": field.name(),
+                    "type": self.spark_type(&path, field.data_type())?,
+                    "nullable": field.is_nullable(),
+                    "metadata": {},
+                }))
+            })
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+        Ok(json!({"type": "struct", "fields": fields}))
+    }
+
+    fn spark_type(&self, path: &str, data_type: &DataType) -> Result<Value, PQRSError> {
+        let spark_type = match value_type(data_type) {
+            DataType::Boolean => json!("boolean"),
+            DataType::Int8 => json!("byte"),
+            DataType::Int16 | DataType::UInt8 => json!("short"),
+            DataType::Int32 | DataType::UInt16 => json!("integer"),
+            DataType::Int64 | DataType::UInt32 => json!("long"),
+            DataType::UInt64 => json!("decimal(20,0)"),
+            DataType::Float16 | DataType::Float32 => json!("float"),
+            DataType::Float64 => json!("double"),
+            DataType::Decimal128(precision, scale)
+            | DataType::Decimal256(precision, scale)
+                if *precision <= 38 && *scale >= 0 =>
+            {
+                json!(format!("decimal({},{})", precision, scale))
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => json!("string"),
+            DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_) => json!("binary"),
+            DataType::Date32 | DataType::Date64 => json!("date"),
+            DataType::Timestamp(_, Some(_)) => json!("timestamp"),
+            DataType::Timestamp(_, None) => json!("timestamp_ntz"),
+            DataType::Struct(fields) => self.spark_struct(path, fields)?,
+            DataType::Map(entries, _) => {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, data_type))?;
+                json!({
+                    "type": "map",
+                    "keyType": self.spark_type(&child_path(path, key.name()), key.data_type())?,
+                    "valueType": self.spark_type(&child_path(path, value.name()), value.data_type())?,
+                    "valueContainsNull": value.is_nullable(),
+                })
+            }
+            value_type => match list_item(value_type) {
+                Some(item) => json!({
+                    "type": "array",
+                    "elementType": self.spark_type(&child_path(path, item.name()), item.data_type())?,
+                    "containsNull": item.is_nullable(),
+                }),
+                None => return Err(self.unsupported(path, data_type)),
+            },
+        };
+        Ok(spark_type)
+    }
+
+    /// A BigQuery column, lists become repeated columns and maps repeated records of
+    /// their keys and values
+    fn bigquery_field(&self, path: &str, field: &Field) -> Result<Value, PQRSError> {
+        let data_type = value_type(field.data_type());
+        let mode = if field.is_nullable() {
+            "NULLABLE"
+        } else {
+            "REQUIRED"
+        };
+
+        if let Some(item) = list_item(data_type) {
+            let item = item.clone().with_name(field.name());
+            let mut column =
+                self.bigquery_field(&child_path(path, item.name()), &item)?;
+            if column["mode"] == "REPEATED" {
+                // BigQuery has no arrays of arrays
+                return Err(self.unsupported(path, field.data_type()));
+            }
+            column["mode"] = json!("REPEATED");
+            return Ok(column);
+        }
+
+        let mut column = json!({"name": field.name(), "mode": mode});
+        let bigquery_type = match data_type {
+            DataType::Boolean => "BOOL",
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32 => "INT64",
+            DataType::UInt64 => "NUMERIC",
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => "FLOAT64",
+            DataType::Decimal128(precision, scale)
+            | DataType::Decimal256(precision, scale)
+                if *scale >= 0 =>
+            {
+                let (precision, scale) = (*precision as i16, *scale as i16);
+                let bigquery_type = if scale <= 9 && precision - scale <= 29 {
+                    "NUMERIC"
+                } else if scale <= 38 && precision - scale <= 38 {
+                    "BIGNUMERIC"
+                } else {
+                    return Err(self.unsupported(path, field.data_type()));
+                };
+                column["precision"] = json!(precision.to_string());
+                column["scale"] = json!(scale.to_string());
+                bigquery_type
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "STRING",
+            DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_) => "BYTES",
+            DataType::Date32 | DataType::Date64 => "DATE",
+            DataType::Time32(_) | DataType::Time64(_) => "TIME",
+            DataType::Timestamp(_, Some(_)) => "TIMESTAMP",
+            DataType::Timestamp(_, None) => "DATETIME",
+            DataType::Interval(_) => "INTERVAL",
+            DataType::Struct(fields) => {
+                column["fields"] = self
+                    .bigquery_fields(path, fields.iter().map(|field| field.as_ref()))?;
+                "RECORD"
+            }
+            DataType::Map(entries, _) => {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, field.data_type()))?;
+                column["fields"] =
+                    self.bigquery_fields(path, vec![key, value].into_iter())?;
+                column["mode"] = json!("REPEATED");
+                "RECORD"
+            }
+            _ => return Err(self.unsupported(path, field.data_type())),
+        };
+        column["type"] = json!(bigquery_type);
+        Ok(column)
+    }
+
+    fn bigquery_fields<'a, I: Iterator<Item = &'a Field>>(
+        &self,
+        path: &str,
+        fields: I,
+    ) -> Result<Value, PQRSError> {
+        let fields = fields
+            .map(|field| self.bigquery_field(&child_path(path, field.name()), field))
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+        Ok(json!(fields))
+    }
+
+    /// An Avro record, its name is the path of the struct for nested records, as Avro
+    /// requires every named type to have a unique name
+    fn avro_record(
+        &self,
+        name: &str,
+        path: &str,
+        fields: &Fields,
+    ) -> Result<Value, PQRSError> {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let path = child_path(path, field.name());
+                let avro_type = self.avro_nullable(&path, field)?;
+                let mut avro_field = json!({"name": field.name(), "type": avro_type});
+                if field.is_nullable() {
+                    avro_field["default"] = Value::Null;
+                }
+                Ok(avro_field)
+            })
+            .collect::<Result<Vec<_>, PQRSError>>()?;
+        Ok(json!({"type": "record", "name": identifier(name), "fields": fields}))
+    }
+
+    /// The Avro type of a field, nullable fields are a union with null
+    fn avro_nullable(&self, path: &str, field: &Field) -> Result<Value, PQRSError> {
+        let avro_type = self.avro_type(path, field.data_type())?;
+        if field.is_nullable() {
+            Ok(json!(["null", avro_type]))
+        } else {
+            Ok(avro_type)
+        }
+    }
+
+    fn avro_type(&self, path: &str, data_type: &DataType) -> Result<Value, PQRSError> {
+        let decimal = |precision: u8, scale: i8| json!({"type": "bytes", "logicalType": "decimal", "precision": precision, "scale": scale});
+        let avro_type = match value_type(data_type) {
+            DataType::Boolean => json!("boolean"),
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::UInt8
+            | DataType::UInt16 => json!("int"),
+            DataType::Int64 | DataType::UInt32 => json!("long"),
+            DataType::UInt64 => decimal(20, 0),
+            DataType::Float16 | DataType::Float32 => json!("float"),
+            DataType::Float64 => json!("double"),
+            DataType::Decimal128(precision, scale)
+            | DataType::Decimal256(precision, scale)
+                if *scale >= 0 =>
+            {
+                decimal(*precision, *scale)
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => json!("string"),
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+                json!("bytes")
+            }
+            DataType::FixedSizeBinary(size) => {
+                json!({"type": "fixed", "name": identifier(path), "size": size})
+            }
+            DataType::Date32 | DataType::Date64 => {
+                json!({"type": "int", "logicalType": "date"})
+            }
+            DataType::Time32(_) => json!({"type": "int", "logicalType": "time-millis"}),
+            DataType::Time64(_) => json!({"type": "long", "logicalType": "time-micros"}),
+            DataType::Timestamp(unit, timezone) => {
+                let precision = match unit {
+                    TimeUnit::Second | TimeUnit::Millisecond => "millis",
+                    TimeUnit::Microsecond => "micros",
+                    TimeUnit::Nanosecond => "nanos",
+                };
+                let local = if timezone.is_some() { "" } else { "local-" };
+                let logical_type = format!("{}timestamp-{}", local, precision);
+                json!({"type": "long", "logicalType": logical_type})
+            }
+            DataType::Interval(IntervalUnit::MonthDayNano) => {
+                // the Avro duration has millisecond precision
+                json!({"type": "fixed", "name": identifier(path), "size": 12, "logicalType": "duration"})
+            }
+            DataType::Struct(fields) => self.avro_record(path, path, fields)?,
+            DataType::Map(entries, _) => {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, data_type))?;
+                // Avro maps only have string keys
+                if !matches!(
+                    value_type(key.data_type()),
+                    DataType::Utf8 | DataType::LargeUtf8
+                ) {
+                    return Err(self.unsupported(path, data_type));
+                }
+                let values =
+                    self.avro_nullable(&child_path(path, value.name()), value)?;
+                json!({"type": "map", "values": values})
+            }
+            value_type => match list_item(value_type) {
+                Some(item) => {
+                    let items =
+                        self.avro_nullable(&child_path(path, item.name()), item)?;
+                    json!({"type": "array", "items": items})
+                }
+                None => return Err(self.unsupported(path, data_type)),
+            },
+        };
+        Ok(avro_type)
+    }
+
+    fn json_schema_object(
+        &self,
+        path: &str,
+        fields: &Fields,
+    ) -> Result<Value, PQRSError> {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        for field in fields.iter() {
+            let path = child_path(path, field.name());
+            properties.insert(
+                field.name().to_string(),
+                self.json_schema_field(&path, field)?,
+            );
+            if !field.is_nullable() {
+                required.push(field.name().to_string());
+            }
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }))
+    }
+
+    /// The JSON Schema of a field, null is one of the types of nullable fields
+    fn json_schema_field(&self, path: &str, field: &Field) -> Result<Value, PQRSError> {
+        let mut schema = self.json_schema_type(path, field.data_type())?;
+        if field.is_nullable() {
+            let json_type = schema["type"].clone();
+            schema["type"] = json!([json_type, "null"]);
+        }
+        Ok(schema)
+    }
+
+    fn json_schema_type(
+        &self,
+        path: &str,
+        data_type: &DataType,
+    ) -> Result<Value, PQRSError> {
+        let schema = match value_type(data_type) {
+            DataType::Boolean => json!({"type": "boolean"}),
+            DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+                json!({"type": "integer"})
+            }
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+                json!({"type": "integer", "minimum": 0})
+            }
+            DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal128(_, _)
+            | DataType::Decimal256(_, _) => json!({"type": "number"}),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                json!({"type": "string"})
+            }
+            DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_) => {
+                json!({"type": "string", "contentEncoding": "base64"})
+            }
+            DataType::Date32 | DataType::Date64 => {
+                json!({"type": "string", "format": "date"})
+            }
+            DataType::Time32(_) | DataType::Time64(_) => {
+                json!({"type": "string", "format": "time"})
+            }
+            DataType::Timestamp(_, _) => json!({"type": "string", "format": "date-time"}),
+            DataType::Duration(_) | DataType::Interval(_) => {
+                json!({"type": "string", "format": "duration"})
+            }
+            DataType::Struct(fields) => self.json_schema_object(path, fields)?,
+            DataType::Map(entries, _) => {
+                let (_, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(path, data_type))?;
+                let values =
+                    self.json_schema_field(&child_path(path, value.name()), value)?;
+                json!({"type": "object", "additionalProperties": values})
+            }
+            value_type => match list_item(value_type) {
+                Some(item) => {
+                    let items =
+                        self.json_schema_field(&child_path(path, item.name()), item)?;
+                    let mut schema = json!({"type": "array", "items": items});
+                    if let DataType::FixedSizeList(_, size) = value_type {
+                        schema["minItems"] = json!(size);
+                        schema["maxItems"] = json!(size);
+                    }
+                    schema
+                }
+                None => return Err(self.unsupported(path, data_type)),
+            },
+        };
+        Ok(schema)
+    }
+
+    fn proto_file(&self, name: &str, fields: &Fields) -> Result<String, PQRSError> {
+        let mut imports = BTreeSet::new();
+        let message =
+            self.proto_message(&camel_case(name), "", fields, &mut imports, "")?;
+
+        let mut file = String::from("syntax = \"proto3\";\n\n");
+        for import in &imports {
+            file.push_str(&format!("import \"{}\";\n", import));
+        }
+        if !imports.is_empty() {
+            file.push('\n');
+        }
+        file.push_str(&message);
+        Ok(file)
+    }
+
+    /// A proto3 message, the messages of struct fields are nested in it
+    fn proto_message(
+        &self,
+        name: &str,
+        path: &str,
+        fields: &Fields,
+        imports: &mut BTreeSet<&'static str>,
+        indent: &str,
+    ) -> Result<String, PQRSError> {
+        let inner = format!("{}  ", indent);
+        let mut lines = vec![];
+        let mut messages = vec![];
+        for (index, field) in fields.iter().enumerate() {
+            let path = child_path(path, field.name());
+            let data_type = value_type(field.data_type());
+            let message_name = camel_case(field.name());
+
+            let (label, proto_type) = if let Some(item) = list_item(data_type) {
+                let item_type = value_type(item.data_type());
+                if list_item(item_type).is_some()
+                    || matches!(item_type, DataType::Map(_, _))
+                {
+                    // repeated fields cannot hold lists or maps
+                    return Err(self.unsupported(&path, field.data_type()));
+                }
+                let item_path = child_path(&path, item.name());
+                let proto_type =
+                    self.proto_type(&item_path, item_type, &message_name, imports)?;
+                if let DataType::Struct(fields) = item_type {
+                    messages.push(self.proto_message(
+                        &message_name,
+                        &item_path,
+                        fields,
+                        imports,
+                        &inner,
+                    )?);
+                }
+                ("repeated ", proto_type)
+            } else if let DataType::Map(entries, _) = data_type {
+                let (key, value) = map_entries(entries)
+                    .ok_or_else(|| self.unsupported(&path, field.data_type()))?;
+                let key_type = value_type(key.data_type());
+                let values_type = value_type(value.data_type());
+                let key_is_valid = key_type.is_integer()
+                    || matches!(
+                        key_type,
+                        DataType::Boolean | DataType::Utf8 | DataType::LargeUtf8
+                    );
+                if !key_is_valid
+                    || list_item(values_type).is_some()
+                    || matches!(values_type, DataType::Map(_, _))
+                {
+                    return Err(self.unsupported(&path, field.data_type()));
+                }
+                let value_path = child_path(&path, value.name());
+                let value_name = format!("{}Value", message_name);
+                let key_type = self.proto_type(&path, key_type, "", imports)?;
+                let proto_value =
+                    self.proto_type(&value_path, values_type, &value_name, imports)?;
+                if let DataType::Struct(fields) = values_type {
+                    messages.push(self.proto_message(
+                        &value_name,
+                        &value_path,
+                        fields,
+                        imports,
+                        &inner,
+                    )?);
+                }
+                ("", format!("map<{}, {}>", key_type, proto_value))
+            } else {
+                let proto_type =
+                    self.proto_type(&path, data_type, &message_name, imports)?;
+                if let DataType::Struct(fields) = data_type {
+                    messages.push(self.proto_message(
+                        &message_name,
+                        &path,
+                        fields,
+                        imports,
+                        &inner,
+                    )?);
+                }
+                // fields of message types always track presence
+                let is_message =
+                    proto_type.contains('.') || matches!(data_type, DataType::Struct(_));
+                let label = if field.is_nullable() && !is_message {
+                    "optional "
+                } else {
+                    ""
+                };
+                (label, proto_type)
+            };
+
+            lines.push(format!(
+                "{}{}{} {} = {};",
+                inner,
+                label,
+                proto_type,
+                identifier(field.name()),
+                index + 1
+            ));
+        }
+
+        let mut message = format!("{}message {} {{\n", indent, name);
+        for line in lines {
+            message.push_str(&line);
+            message.push('\n');
+        }
+        for nested in messages {
+            message.push('\n');
+            message.push_str(&nested);
+        }
+        message.push_str(&format!("{}}}\n", indent));
+        Ok(message)
+    }
+
+    /// The proto3 type of a value that is not a list or map. Logical types use the
+    /// well known types and the common types of the Google APIs.
+    fn proto_type(
+        &self,
+        path: &str,
+        data_type: &DataType,
+        message_name: &str,
+        imports: &mut BTreeSet<&'static str>,
+    ) -> Result<String, PQRSError> {
+        let mut import = |import: &'static str, proto_type: &str| {
+            imports.insert(import);
+            proto_type.to_string()
+        };
+        let proto_type = match data_type {
+            DataType::Boolean => "bool".to_string(),
+            DataType::Int8 | DataType::Int16 | DataType::Int32 => "int32".to_string(),
+            DataType::Int64 => "int64".to_string(),
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => "uint32".to_string(),
+            DataType::UInt64 => "uint64".to_string(),
+            DataType::Float16 | DataType::Float32 => "float".to_string(),
+            DataType::Float64 => "double".to_string(),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                "string".to_string()
+            }
+            DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_) => "bytes".to_string(),
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                import("google/type/decimal.proto", "google.type.Decimal")
+            }
+            DataType::Date32 | DataType::Date64 => {
+                import("google/type/date.proto", "google.type.Date")
+            }
+            DataType::Time32(_) | DataType::Time64(_) => {
+                import("google/type/timeofday.proto", "google.type.TimeOfDay")
+            }
+            DataType::Timestamp(_, _) => import(
+                "google/protobuf/timestamp.proto",
+                "google.protobuf.Timestamp",
+            ),
+            DataType::Duration(_) => {
+                import("google/protobuf/duration.proto", "google.protobuf.Duration")
+            }
+            DataType::Struct(_) => message_name.to_string(),
+            _ => return Err(self.unsupported(path, data_type)),
+        };
+        Ok(proto_type)
+    }
+}
+
+/// A node of the Parquet schema as printed by schema --json. It keeps everything that is
+/// needed to build the schema again, see [`SchemaNode::to_type`].
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SchemaNode {
+    Group {
+        name: String,
+        /// The repetition, which the root of the schema does not have
+        #[serde(skip_serializing_if = "Option::is_none")]
+        repetition: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        field_id: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        logical_type: Option<LogicalTypeJson>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        converted_type: Option<String>,
+        fields: Vec<SchemaNode>,
+    },
+    Primitive {
+        name: String,
+        repetition: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        field_id: Option<i32>,
+        physical_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        type_length: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        logical_type: Option<LogicalTypeJson>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        converted_type: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        precision: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        scale: Option<i32>,
+    },
+}
+
+/// A Parquet logical type with its parameters
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LogicalTypeJson {
+    String,
+    Map,
+    List,
+    Enum,
+    Decimal {
+        precision: i32,
+        scale: i32,
+    },
+    Date,
+    Time {
+        unit: TimeUnitJson,
+        adjusted_to_utc: bool,
+    },
+    Timestamp {
+        unit: TimeUnitJson,
+        adjusted_to_utc: bool,
+    },
+    Integer {
+        bit_width: i8,
+        signed: bool,
+    },
+    Unknown,
+    Json,
+    Bson,
+    Uuid,
+    Float16,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TimeUnitJson {
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl From<&ParquetTimeUnit> for TimeUnitJson {
+    fn from(unit: &ParquetTimeUnit) -> Self {
+        match unit {
+            ParquetTimeUnit::MILLIS(_) => TimeUnitJson::Millis,
+            ParquetTimeUnit::MICROS(_) => TimeUnitJson::Micros,
+            ParquetTimeUnit::NANOS(_) => TimeUnitJson::Nanos,
+        }
+    }
+}
+
+impl From<&TimeUnitJson> for ParquetTimeUnit {
+    fn from(unit: &TimeUnitJson) -> Self {
+        match unit {
+            TimeUnitJson::Millis => ParquetTimeUnit::MILLIS(Default::default()),
+            TimeUnitJson::Micros => ParquetTimeUnit::MICROS(Default::default()),
+            TimeUnitJson::Nanos => ParquetTimeUnit::NANOS(Default::default()),
+        }
+    }
+}
+
+impl From<&LogicalType> for LogicalTypeJson {
+    fn from(logical_type: &LogicalType) -> Self {
+        match logical_type {
+            LogicalType::String => LogicalTypeJson::String,
+            LogicalType::Map => LogicalTypeJson::Map,
+            LogicalType::List => LogicalTypeJson::List,
+            LogicalType::Enum => LogicalTypeJson::Enum,
+            LogicalType::Decimal { scale, precision } => LogicalTypeJson::Decimal {
+                precision: *precision,
+                scale: *scale,
+            },
+            LogicalType::Date => LogicalTypeJson::Date,
+            LogicalType::Time {
+                is_adjusted_to_u_t_c,
+                unit,
+            } => LogicalTypeJson::Time {
+                unit: unit.into(),
+                adjusted_to_utc: *is_adjusted_to_u_t_c,
+            },
+            LogicalType::Timestamp {
+                is_adjusted_to_u_t_c,
+                unit,
+            } => LogicalTypeJson::Timestamp {
+                unit: unit.into(),
+                adjusted_to_utc: *is_adjusted_to_u_t_c,
+            },
+            LogicalType::Integer {
+                bit_width,
+                is_signed,
+            } => LogicalTypeJson::Integer {
+                bit_width: *bit_width,
+                signed: *is_signed,
+            },
+            LogicalType::Unknown => LogicalTypeJson::Unknown,
+            LogicalType::Json => LogicalTypeJson::Json,
+            LogicalType::Bson => LogicalTypeJson::Bson,
+            LogicalType::Uuid => LogicalTypeJson::Uuid,
+            LogicalType::Float16 => LogicalTypeJson::Float16,
+        }
+    }
+}
+
+impl From<&LogicalTypeJson> for LogicalType {
+    fn from(logical_type: &LogicalTypeJson) -> Self {
+        match logical_type {
+            LogicalTypeJson::String => LogicalType::String,
+            LogicalTypeJson::Map => LogicalType::Map,
+            LogicalTypeJson::List => LogicalType::List,
+            LogicalTypeJson::Enum => LogicalType::Enum,
+            LogicalTypeJson::Decimal { precision, scale } => LogicalType::Decimal {
+                scale: *scale,
+                precision: *precision,
+            },
+            LogicalTypeJson::Date => LogicalType::Date,
+            LogicalTypeJson::Time {
+                unit,
+                adjusted_to_utc,
+            } => LogicalType::Time {
+                is_adjusted_to_u_t_c: *adjusted_to_utc,
+                unit: unit.into(),
+            },
+            LogicalTypeJson::Timestamp {
+                unit,
+                adjusted_to_utc,
+            } => LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: *adjusted_to_utc,
+                unit: unit.into(),
+            },
+            LogicalTypeJson::Integer { bit_width, signed } => LogicalType::Integer {
+                bit_width: *bit_width,
+                is_signed: *signed,
+            },
+            LogicalTypeJson::Unknown => LogicalType::Unknown,
+            LogicalTypeJson::Json => LogicalType::Json,
+            LogicalTypeJson::Bson => LogicalType::Bson,
+            LogicalTypeJson::Uuid => LogicalType::Uuid,
+            LogicalTypeJson::Float16 => LogicalType::Float16,
+        }
+    }
+}
+
+/// The value, unless it is the default of the Parquet schema for an unset value
+fn set<T: PartialEq>(value: T, unset: T) -> Option<T> {
+    if value == unset {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+impl From<&Type> for SchemaNode {
+    fn from(parquet_type: &Type) -> Self {
+        let info = parquet_type.get_basic_info();
+        let repetition = info.has_repetition().then(|| info.repetition().to_string());
+        let field_id = info.has_id().then(|| info.id());
+        let logical_type = info.logical_type().as_ref().map(LogicalTypeJson::from);
+        let converted_type = set(info.converted_type(), ConvertedType::NONE)
+            .map(|converted_type| converted_type.to_string());
+
+        match parquet_type {
+            Type::GroupType { fields, .. } => SchemaNode::Group {
+                name: info.name().to_string(),
+                repetition,
+                field_id,
+                logical_type,
+                converted_type,
+                fields: fields.iter().map(|field| field.as_ref().into()).collect(),
+            },
+            Type::PrimitiveType {
+                physical_type,
+                type_length,
+                scale,
+                precision,
+                ..
+            } => SchemaNode::Primitive {
+                name: info.name().to_string(),
+                repetition: repetition.unwrap_or_default(),
+                field_id,
+                physical_type: physical_type.to_string(),
+                type_length: set(*type_length, -1),
+                logical_type,
+                converted_type,
+                precision: set(*precision, -1),
+                scale: set(*scale, -1),
+            },
+        }
+    }
+}
+
+impl SchemaNode {
+    /// Build the Parquet type of this node and its children
+    pub fn to_type(&self) -> Result<Type, ParquetError> {
+        match self {
+            SchemaNode::Group {
+                name,
+                repetition,
+                field_id,
+                logical_type,
+                converted_type,
+                fields,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| field.to_type().map(Arc::new))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut builder = Type::group_type_builder(name)
+                    .with_id(*field_id)
+                    .with_logical_type(logical_type.as_ref().map(LogicalType::from))
+                    .with_converted_type(converted_type_from(converted_type)?)
+                    .with_fields(fields);
+                if let Some(repetition) = repetition {
+                    builder = builder.with_repetition(repetition.parse()?);
+                }
+                builder.build()
+            }
+            SchemaNode::Primitive {
+                name,
+                repetition,
+                field_id,
+                physical_type,
+                type_length,
+                logical_type,
+                converted_type,
+                precision,
+                scale,
+            } => Type::primitive_type_builder(name, physical_type.parse()?)
+                .with_repetition(repetition.parse()?)
+                .with_id(*field_id)
+                .with_length(type_length.unwrap_or(-1))
+                .with_logical_type(logical_type.as_ref().map(LogicalType::from))
+                .with_converted_type(converted_type_from(converted_type)?)
+                .with_precision(precision.unwrap_or(-1))
+                .with_scale(scale.unwrap_or(-1))
+                .build(),
+        }
+    }
+}
+
+fn converted_type_from(
+    converted_type: &Option<String>,
+) -> Result<ConvertedType, ParquetError> {
+    match converted_type {
+        Some(converted_type) => converted_type.parse(),
+        None => Ok(ConvertedType::NONE),
+    }
+}
@@ -1,24 +1,84 @@
 use crate::errors::PQRSError;
 use crate::errors::PQRSError::CouldNotOpenFile;
+use crate::progress::Progress;
+use crate::remote::{is_remote, RemoteFile};
+use crate::render::{format_binary, BinaryFormat, Renderer};
 use arrow::{datatypes::Schema, record_batch::RecordBatch};
+use bytes::Bytes;
+use clap::Args;
 use log::debug;
-use parquet::arrow::{arrow_reader::ArrowReaderBuilder};
-use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::arrow::arrow_reader::{
+    ArrowReaderBuilder, ArrowReaderMetadata, ArrowReaderOptions,
+    ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::ProjectionMask;
+use parquet::basic::{Encoding, Type as PhysicalType};
+use parquet::errors::ParquetError;
+use parquet::file::footer::{decode_footer, decode_metadata};
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData};
+use parquet::file::reader::{ChunkReader, FileReader, Length};
+use parquet::file::serialized_reader::{ReadOptionsBuilder, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use parquet::file::FOOTER_SIZE;
+use parquet::format::{self, BloomFilterHeader, PageHeader};
+use parquet::record::reader::RowIter;
 use parquet::record::Row;
+use parquet::thrift::TSerializable;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
-use std::cmp::min;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::{max, min};
+use std::convert::{TryFrom, TryInto};
 use std::fs::File;
-use std::ops::Add;
-use std::path::Path;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::{Add, AddAssign};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::sync_channel;
+use std::sync::Arc;
+use thrift::protocol::TCompactInputProtocol;
 use walkdir::DirEntry;
 
-// calculate the sizes in bytes for one KiB, MiB, GiB, TiB, PiB
-static ONE_KI_B: i64 = 1024;
-static ONE_MI_B: i64 = ONE_KI_B * 1024;
-static ONE_GI_B: i64 = ONE_MI_B * 1024;
-static ONE_TI_B: i64 = ONE_GI_B * 1024;
-static ONE_PI_B: i64 = ONE_TI_B * 1024;
+// the IEC units are powers of 1024, the SI units powers of 1000
+static IEC_UNITS: [SizeUnit; 6] = [
+    SizeUnit::new(1, "B"),
+    SizeUnit::new(1 << 10, "KiB"),
+    SizeUnit::new(1 << 20, "MiB"),
+    SizeUnit::new(1 << 30, "GiB"),
+    SizeUnit::new(1 << 40, "TiB"),
+    SizeUnit::new(1 << 50, "PiB"),
+];
+static SI_UNITS: [SizeUnit; 6] = [
+    SizeUnit::new(1, "B"),
+    SizeUnit::new(1_000, "kB"),
+    SizeUnit::new(1_000_000, "MB"),
+    SizeUnit::new(1_000_000_000, "GB"),
+    SizeUnit::new(1_000_000_000_000, "TB"),
+    SizeUnit::new(1_000_000_000_000_000, "PB"),
+];
+
+/// The number of rows decoded at a time when reading record batches
+static BATCH_SIZE: usize = 8192;
+
+/// The number of files processed per thread before output is printed in order
+static FILES_PER_THREAD: usize = 16;
+
+/// The number of rendered rows of a row group that are held while waiting to be printed
+static ROWS_IN_FLIGHT: usize = 1024;
+
+/// The bytes read to find the length of a bloom filter, more than its header needs
+pub static BLOOM_FILTER_HEADER_SIZE: usize = 64;
+
+/// The bytes read for a page header at first, which are read again in larger steps
+/// for headers whose statistics hold large values
+pub static PAGE_HEADER_READ_SIZE: usize = 16 * 1024;
+
+/// The path used to read from stdin or write to stdout
+pub static STDIO_PATH: &str = "-";
 
 /// Output formats supported. Only cat command support CSV format.
 #[derive(Copy, Clone, Debug)]
@@ -35,23 +95,372 @@ impl std::fmt::Display for Formats {
     }
 }
 
-/// Check if a particular path is present on the filesystem
+/// Check if the given path refers to stdin/stdout, i.e. is `-`
+pub fn is_stdio<P: AsRef<Path>>(file_path: P) -> bool {
+    file_path.as_ref() == Path::new(STDIO_PATH)
+}
+
+/// Check if a particular path is present on the filesystem or in the object store
 pub fn check_path_present<P: AsRef<Path>>(file_path: P) -> bool {
-    Path::new(file_path.as_ref()).exists()
+    if is_remote(&file_path) {
+        return RemoteFile::open(&file_path).is_ok();
+    }
+    is_stdio(&file_path) || Path::new(file_path.as_ref()).exists()
+}
+
+/// Where the bytes of a parquet file are read from
+#[derive(Debug)]
+enum FileSource {
+    Local(File),
+    Remote(RemoteFile),
+}
+
+/// Counters for the IO performed on a file, shared between all its handles
+#[derive(Debug, Default)]
+pub struct IoStats {
+    bytes_read: AtomicU64,
+    seeks: AtomicU64,
+}
+
+impl IoStats {
+    fn record_seek(&self) {
+        self.seeks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_read(&self, bytes: usize) {
+        self.bytes_read.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// The number of bytes read from the file so far
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// The number of positioned reads, i.e. seeks or ranged requests, made so far
+    pub fn seeks(&self) -> u64 {
+        self.seeks.load(Ordering::Relaxed)
+    }
+}
+
+/// A parquet file that is either on the local filesystem or in a remote object store
+#[derive(Debug)]
+pub struct ParquetFile {
+    path: PathBuf,
+    source: FileSource,
+    stats: Arc<IoStats>,
+}
+
+impl ParquetFile {
+    fn new(path: &Path, source: FileSource) -> ParquetFile {
+        ParquetFile {
+            path: path.to_path_buf(),
+            source,
+            stats: Arc::new(IoStats::default()),
+        }
+    }
+
+    /// Create another handle to the same underlying file
+    pub fn try_clone(&self) -> Result<ParquetFile, PQRSError> {
+        let source = match &self.source {
+            FileSource::Local(file) => FileSource::Local(file.try_clone()?),
+            FileSource::Remote(file) => FileSource::Remote(file.clone()),
+        };
+        Ok(ParquetFile {
+            path: self.path.clone(),
+            source,
+            stats: self.stats.clone(),
+        })
+    }
+
+    /// The path or URL the file was opened from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The IO performed on the file through any of its handles
+    pub fn stats(&self) -> &IoStats {
+        &self.stats
+    }
+}
+
+impl Length for ParquetFile {
+    fn len(&self) -> u64 {
+        match &self.source {
+            FileSource::Local(file) => file.len(),
+            FileSource::Remote(file) => file.len(),
+        }
+    }
+}
+
+impl ChunkReader for ParquetFile {
+    type T = CountingReader;
+
+    fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
+        self.stats.record_seek();
+        let inner: Box<dyn Read + Send> = match &self.source {
+            FileSource::Local(file) => Box::new(io::BufReader::new(LocalReader {
+                file: file.try_clone()?,
+                position: start,
+            })),
+            FileSource::Remote(file) => Box::new(file.get_read(start)?),
+        };
+        Ok(CountingReader {
+            inner,
+            stats: self.stats.clone(),
+        })
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
+        self.stats.record_seek();
+        let bytes = match &self.source {
+            FileSource::Local(file) => {
+                let mut buffer = vec![0; length];
+                let mut reader = LocalReader {
+                    file: file.try_clone()?,
+                    position: start,
+                };
+                reader.read_exact(&mut buffer)?;
+                Bytes::from(buffer)
+            }
+            FileSource::Remote(file) => file.get_bytes(start, length)?,
+        };
+        self.stats.record_read(bytes.len());
+        Ok(bytes)
+    }
 }
 
-/// Open the file based on the pat and return the File object, else return error
-pub fn open_file<P: AsRef<Path>>(file_name: P) -> Result<File, PQRSError> {
+/// A reader over a local file that reads at its own position instead of the cursor
+/// of the file, which is shared by all the handles created with `try_clone`.
+/// This allows the row groups of a file to be read from several threads at once.
+struct LocalReader {
+    file: File,
+    position: u64,
+}
+
+impl Read for LocalReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(unix)]
+        let n = std::os::unix::fs::FileExt::read_at(&self.file, buf, self.position)?;
+        #[cfg(windows)]
+        let n = std::os::windows::fs::FileExt::seek_read(&self.file, buf, self.position)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+/// A reader that records the number of bytes read through it
+pub struct CountingReader {
+    inner: Box<dyn Read + Send>,
+    stats: Arc<IoStats>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.stats.record_read(n);
+        Ok(n)
+    }
+}
+
+/// Open the file based on the path or URL and return the ParquetFile object, else return error
+pub fn open_file<P: AsRef<Path>>(file_name: P) -> Result<ParquetFile, PQRSError> {
     let file_name = file_name.as_ref();
+    if is_stdio(file_name) {
+        return Ok(ParquetFile::new(
+            file_name,
+            FileSource::Local(buffer_stdin().map_err(|e| e.in_file(file_name))?),
+        ));
+    }
+    if is_remote(file_name) {
+        return Ok(ParquetFile::new(
+            file_name,
+            FileSource::Remote(
+                RemoteFile::open(file_name).map_err(|e| e.in_file(file_name))?,
+            ),
+        ));
+    }
+
     let path = Path::new(file_name);
     let file = match File::open(path) {
         Err(_) => return Err(CouldNotOpenFile(file_name.to_path_buf())),
         Ok(f) => f,
     };
 
+    Ok(ParquetFile::new(file_name, FileSource::Local(file)))
+}
+
+/// Options for the commands that only need the footer of the file
+#[derive(Args, Debug, Clone, Default)]
+pub struct FooterArgs {
+    /// Number of bytes to read from the end of the file in the first read,
+    /// which saves a second read or request when the footer fits in it
+    #[arg(long, value_name = "BYTES")]
+    prefetch: Option<usize>,
+
+    /// Report the number of bytes read and seeks made for each file on stderr
+    #[arg(long)]
+    verbose_io: bool,
+}
+
+impl FooterArgs {
+    /// Print the IO performed on the file if asked for
+    pub fn report_io<P: AsRef<Path>>(&self, file_name: P, file: &ParquetFile) {
+        if self.verbose_io {
+            eprintln!(
+                "IO for {}: {} bytes read, {} seeks",
+                file_name.as_ref().display(),
+                file.stats().bytes_read(),
+                file.stats().seeks()
+            );
+        }
+    }
+}
+
+/// The metadata in the footer of a file, and the number of bytes it takes up there
+pub struct Footer {
+    pub metadata: ParquetMetaData,
+    pub metadata_len: usize,
+}
+
+/// Read only the metadata in the footer of the file, without touching any data pages.
+pub fn read_metadata(
+    file: &ParquetFile,
+    footer_args: &FooterArgs,
+) -> Result<ParquetMetaData, PQRSError> {
+    Ok(read_footer(file, footer_args)?.metadata)
+}
+
+/// Read the footer of the file, without touching any data pages. The last 8 bytes
+/// hold the length of the metadata, which is read right before them. A prefetch hint
+/// reads a larger tail up front to do both in a single read.
+pub fn read_footer(
+    file: &ParquetFile,
+    footer_args: &FooterArgs,
+) -> Result<Footer, PQRSError> {
+    let file_size = file.len();
+    if file_size < FOOTER_SIZE as u64 {
+        return Err(ParquetError::General(format!(
+            "File of {} bytes is too small to be a parquet file",
+            file_size
+        ))
+        .into());
+    }
+
+    let prefetch = max(footer_args.prefetch.unwrap_or(0), FOOTER_SIZE);
+    let tail_len = min(prefetch as u64, file_size) as usize;
+    let tail = file.get_bytes(file_size - tail_len as u64, tail_len)?;
+
+    let mut footer = [0_u8; FOOTER_SIZE];
+    footer.copy_from_slice(&tail[tail_len - FOOTER_SIZE..]);
+    let metadata_len = decode_footer(&footer)?;
+    let footer_len = metadata_len + FOOTER_SIZE;
+    if footer_len as u64 > file_size {
+        return Err(ParquetError::General(format!(
+            "Metadata length {} is larger than the file of {} bytes",
+            metadata_len, file_size
+        ))
+        .into());
+    }
+
+    debug!(
+        "Metadata length is {} bytes, prefetched {} bytes",
+        metadata_len, tail_len
+    );
+    let metadata = if footer_len <= tail_len {
+        decode_metadata(&tail[tail_len - footer_len..tail_len - FOOTER_SIZE])?
+    } else {
+        decode_metadata(&file.get_bytes(file_size - footer_len as u64, metadata_len)?)?
+    };
+    Ok(Footer {
+        metadata,
+        metadata_len,
+    })
+}
+
+/// Decode the thrift page header at the start of the buffer, returning the header
+/// and the number of bytes it takes up. The page data follows right after it.
+pub fn read_page_header(buf: &[u8]) -> Result<(PageHeader, usize), ParquetError> {
+    read_thrift(buf)
+}
+
+/// Read the header of every page of the column chunk, which starts with the dictionary
+/// page if there is one. Returns the offset of each page in the file, its header and
+/// the number of bytes the header takes up. Only the headers are read, skipping over
+/// the data of each page.
+pub fn read_page_headers(
+    file: &ParquetFile,
+    column: &ColumnChunkMetaData,
+) -> Result<Vec<(u64, PageHeader, usize)>, PQRSError> {
+    let (start, length) = column.byte_range();
+
+    let mut headers = vec![];
+    let mut position = 0;
+    while position < length {
+        let offset = start + position;
+        let remaining = (length - position) as usize;
+        let mut read_size = min(PAGE_HEADER_READ_SIZE, remaining);
+        let (header, header_size) = loop {
+            let data = file.get_bytes(offset, read_size)?;
+            match read_page_header(&data) {
+                Ok(header) => break header,
+                Err(_) if read_size < remaining => {
+                    read_size = min(read_size * 2, remaining)
+                }
+                Err(e) => {
+                    return Err(ParquetError::General(format!(
+                        "Could not decode the page header at offset {} of column {}: {}",
+                        offset,
+                        column.column_path().string(),
+                        e
+                    ))
+                    .into())
+                }
+            }
+        };
+        position += (header_size + header.compressed_page_size.max(0) as usize) as u64;
+        headers.push((offset, header, header_size));
+    }
+
+    Ok(headers)
+}
+
+/// Decode the thrift struct at the start of the buffer, returning the struct and the
+/// number of bytes it takes up
+pub fn read_thrift<T: TSerializable>(buf: &[u8]) -> Result<(T, usize), ParquetError> {
+    let mut remaining = buf;
+    let value = {
+        let mut protocol = TCompactInputProtocol::new(&mut remaining);
+        T::read_from_in_protocol(&mut protocol).map_err(|e| match e {
+            thrift::Error::Protocol(e) => ParquetError::General(e.message),
+            thrift::Error::Transport(e) => ParquetError::General(e.message),
+            e => ParquetError::External(Box::new(e)),
+        })?
+    };
+    Ok((value, buf.len() - remaining.len()))
+}
+
+/// Buffer all of stdin into an anonymous temporary file.
+/// The parquet footer is at the end of the file, so the data cannot be streamed.
+fn buffer_stdin() -> Result<File, PQRSError> {
+    let mut file = tempfile::tempfile()?;
+    io::copy(&mut io::stdin().lock(), &mut file)?;
+    file.seek(SeekFrom::Start(0))?;
+    debug!("Buffered {} bytes from stdin", file.metadata()?.len());
+
     Ok(file)
 }
 
+/// Create the file to write parquet data to, or use stdout if the path is `-`
+pub fn create_output<P: AsRef<Path>>(
+    file_name: P,
+) -> Result<Box<dyn Write + Send>, PQRSError> {
+    if is_stdio(&file_name) {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Ok(Box::new(File::create(file_name)?))
+    }
+}
+
 /// Check if the given entry in the walking tree is a hidden file
 pub fn is_hidden(entry: &DirEntry) -> bool {
     entry
@@ -61,40 +470,532 @@ pub fn is_hidden(entry: &DirEntry) -> bool {
         .unwrap_or(false)
 }
 
-/// Print the given number of records in either json or json-like format
+/// Create a thread pool with the given number of threads, or one per CPU if 0
+pub fn thread_pool(jobs: usize) -> Result<ThreadPool, PQRSError> {
+    Ok(ThreadPoolBuilder::new().num_threads(jobs).build()?)
+}
+
+/// Run `work` for each of the files using `jobs` threads, then `output` for each of the
+/// results in the order of the given files. Files are processed a window at a time, so
+/// that output starts early and memory use stays bounded for large numbers of files.
+pub fn for_each_file<T, W, O>(
+    files: &[PathBuf],
+    jobs: usize,
+    work: W,
+    mut output: O,
+) -> Result<(), PQRSError>
+where
+    T: Send,
+    W: Fn(&PathBuf) -> Result<T, PQRSError> + Sync,
+    O: FnMut(&PathBuf, T) -> Result<(), PQRSError>,
+{
+    let pool = thread_pool(jobs)?;
+    let window_size = pool.current_num_threads() * FILES_PER_THREAD;
+
+    for window in files.chunks(window_size) {
+        let results = pool.install(|| window.par_iter().map(&work).collect::<Vec<_>>());
+        for (file_name, result) in window.iter().zip(results) {
+            let result = result.map_err(|e| e.in_file(file_name))?;
+            output(file_name, result)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The file name given to the total of all files in a summary
+pub static TOTAL_LABEL: &str = "total";
+
+/// A summary of each file, printed after all files were processed
+pub struct Summary<T> {
+    records: Vec<T>,
+    total: T,
+}
+
+#[derive(Serialize)]
+struct SummaryJson<'a, T> {
+    files: &'a [T],
+    total: SummaryTotal<'a, T>,
+}
+
+/// The total record, with the same fields in the same order as the records of the
+/// files, preceded by the number of files
+#[derive(Serialize)]
+struct SummaryTotal<'a, T> {
+    files: usize,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+impl<T: Serialize + AddAssign + Clone> Summary<T> {
+    /// Start a summary with the given empty record as the total, whose file is named
+    /// TOTAL_LABEL
+    pub fn new(total: T) -> Summary<T> {
+        Summary {
+            records: vec![],
+            total,
+        }
+    }
+
+    pub fn add(&mut self, record: T) {
+        self.total += record.clone();
+        self.records.push(record);
+    }
+
+    pub fn total(&self) -> &T {
+        &self.total
+    }
+
+    /// Print a JSON document with the records of the files and their total
+    pub fn print_json(&self) -> Result<(), PQRSError> {
+        let summary = SummaryJson {
+            files: &self.records,
+            total: SummaryTotal {
+                files: self.records.len(),
+                record: &self.total,
+            },
+        };
+        println!("{}", serde_json::to_string(&summary)?);
+        Ok(())
+    }
+
+    /// Print a CSV row for each file, and one for their total when there is more than
+    /// one file
+    pub fn print_csv(&self) -> Result<(), PQRSError> {
+        let mut writer = csv::Writer::from_writer(io::stdout());
+        for record in &self.records {
+            writer.serialize(record)?;
+        }
+        if self.records.len() > 1 {
+            writer.serialize(&self.total)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// An iterator over the record batches decoded from a file
+type Batches = Box<dyn Iterator<Item = Result<RecordBatch, PQRSError>>>;
+
+/// The row groups that could not be read, which are skipped instead of stopping
+/// the command when `--skip-corrupt` is used
+#[derive(Debug, Default)]
+pub struct Skipped {
+    enabled: bool,
+    row_groups: Vec<PQRSError>,
+}
+
+impl Skipped {
+    pub fn new(enabled: bool) -> Skipped {
+        Skipped {
+            enabled,
+            row_groups: vec![],
+        }
+    }
+
+    /// Skip the row group in the error if skipping is enabled, else return the error
+    pub fn skip(&mut self, error: PQRSError) -> Result<(), PQRSError> {
+        match error {
+            PQRSError::CorruptRowGroup { .. } if self.enabled => {
+                eprintln!("Skipping corrupt data: {}", error.messages().join(": "));
+                self.row_groups.push(error);
+                Ok(())
+            }
+            error => Err(error),
+        }
+    }
+
+    /// Print the row groups that were skipped on stderr
+    pub fn print_summary(&self) {
+        if self.row_groups.is_empty() {
+            return;
+        }
+
+        let rows: i64 = self
+            .row_groups
+            .iter()
+            .map(|error| match error {
+                PQRSError::CorruptRowGroup { rows_skipped, .. } => *rows_skipped,
+                _ => 0,
+            })
+            .sum();
+        eprintln!(
+            "Skipped {} rows in {} row groups that could not be read:",
+            rows,
+            self.row_groups.len()
+        );
+        for error in &self.row_groups {
+            eprintln!("  {}", error.messages().join(": "));
+        }
+    }
+}
+
+/// Wrap an error reading the given row group with the file, the row group and the
+/// first column that cannot be decoded on its own
+fn corrupt_row_group<E: Into<Box<dyn std::error::Error + Send + Sync>>>(
+    file: &ParquetFile,
+    metadata: &ParquetMetaData,
+    row_group: usize,
+    rows_read: usize,
+    source: E,
+) -> PQRSError {
+    PQRSError::CorruptRowGroup {
+        file: file.path().to_path_buf(),
+        row_group,
+        column: find_corrupt_column(file, row_group),
+        rows_skipped: metadata.row_group(row_group).num_rows() - rows_read as i64,
+        source: source.into(),
+    }
+}
+
+/// Decode each leaf column of the row group separately to find the first one that
+/// cannot be read. This is only done after reading the row group failed.
+fn find_corrupt_column(file: &ParquetFile, row_group: usize) -> Option<String> {
+    let metadata = ArrowReaderMetadata::load(file, ArrowReaderOptions::default()).ok()?;
+    let schema = metadata.metadata().file_metadata().schema_descr_ptr();
+
+    let decode = |column: usize| -> Result<(), PQRSError> {
+        let batch_reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            file.try_clone()?,
+            metadata.clone(),
+        )
+        .with_row_groups(vec![row_group])
+        .with_projection(ProjectionMask::leaves(&schema, vec![column]))
+        .build()?;
+        for batch in batch_reader {
+            batch?;
+        }
+        Ok(())
+    };
+
+    (0..schema.num_columns())
+        .find(|column| decode(*column).is_err())
+        .map(|column| schema.column(column).path().string())
+}
+
+/// Load the metadata for the arrow reader, together with the page index if asked for.
+/// With the page index the reader only reads the pages that hold the rows it selects,
+/// e.g. the first pages when a limit is set. A page index that cannot be read is
+/// ignored, as the data can be read without it.
+fn load_reader_metadata(
+    file: &ParquetFile,
+    page_index: bool,
+) -> Result<ArrowReaderMetadata, PQRSError> {
+    if page_index {
+        let options = ArrowReaderOptions::new().with_page_index(true);
+        match ArrowReaderMetadata::load(file, options) {
+            Ok(metadata) => return Ok(metadata),
+            Err(e) => debug!("Reading without the page index: {}", e),
+        }
+    }
+    Ok(ArrowReaderMetadata::load(
+        file,
+        ArrowReaderOptions::default(),
+    )?)
+}
+
+/// Open the file for reading rows, together with the page index if asked for. The row
+/// reader expects an offset index for every column chunk once the page index is
+/// loaded, so it is only loaded when the file has one for all of them.
+fn open_row_reader(
+    file: &ParquetFile,
+    page_index: bool,
+) -> Result<SerializedFileReader<ParquetFile>, PQRSError> {
+    let reader = SerializedFileReader::new(file.try_clone()?)?;
+    let indexed = reader
+        .metadata()
+        .row_groups()
+        .iter()
+        .flat_map(|row_group| row_group.columns())
+        .all(|column| column.offset_index_offset().is_some());
+    if !page_index || !indexed {
+        return Ok(reader);
+    }
+
+    let options = ReadOptionsBuilder::new().with_page_index().build();
+    match SerializedFileReader::new_with_options(file.try_clone()?, options) {
+        Ok(reader) => Ok(reader),
+        Err(e) => {
+            debug!("Reading without the page index: {}", e);
+            Ok(reader)
+        }
+    }
+}
+
+/// Decode the record batches of the file, decoding up to `jobs` row groups at a time in
+/// parallel. The batches are returned in the order they appear in the file.
+/// When there is a limit, no more than that many rows are decoded from each row group
+/// and the page index is used to skip the pages after them.
+fn read_batches(
+    file: ParquetFile,
+    jobs: usize,
+    limit: Option<usize>,
+) -> Result<(ArrowReaderMetadata, Batches), PQRSError> {
+    let metadata = load_reader_metadata(&file, limit.is_some())?;
+    let row_groups = (0..metadata.metadata().num_row_groups()).collect::<Vec<_>>();
+    let reader_metadata = metadata.clone();
+
+    if jobs == 1 {
+        let batches = row_groups.into_iter().flat_map(move |row_group| {
+            read_row_group(&file, &reader_metadata, row_group, limit)
+        });
+        return Ok((metadata, Box::new(batches)));
+    }
+
+    let pool = thread_pool(jobs)?;
+    let windows = row_groups
+        .chunks(pool.current_num_threads())
+        .map(|window| window.to_vec())
+        .collect::<Vec<_>>();
+
+    let batches = windows.into_iter().flat_map(move |window| {
+        pool.install(|| {
+            window
+                .par_iter()
+                .map(|row_group| {
+                    read_row_group(&file, &reader_metadata, *row_group, limit)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+    });
+
+    Ok((metadata, Box::new(batches)))
+}
+
+/// Decode the record batches of a single row group, up to the limit of rows, stopping
+/// at the first error
+fn read_row_group(
+    file: &ParquetFile,
+    metadata: &ArrowReaderMetadata,
+    row_group: usize,
+    limit: Option<usize>,
+) -> Batches {
+    let build = || -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+        let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            file.try_clone()?,
+            metadata.clone(),
+        )
+        .with_row_groups(vec![row_group])
+        .with_batch_size(BATCH_SIZE);
+        if let Some(limit) = limit {
+            builder = builder.with_limit(limit);
+        }
+        let batch_reader = builder.build()?;
+        Ok((batch_reader, file.try_clone()?))
+    };
+    let (batch_reader, file) = match build() {
+        Ok(built) => built,
+        Err(e) => {
+            let error = corrupt_row_group(file, metadata.metadata(), row_group, 0, e);
+            return Box::new(std::iter::once(Err(error)));
+        }
+    };
+
+    let metadata = metadata.metadata().clone();
+    let mut rows_read = 0;
+    let mut failed = false;
+    Box::new(batch_reader.map_while(move |batch| {
+        if failed {
+            return None;
+        }
+        match batch {
+            Ok(batch) => {
+                rows_read += batch.num_rows();
+                Some(Ok(batch))
+            }
+            Err(e) => {
+                failed = true;
+                Some(Err(corrupt_row_group(
+                    &file, &metadata, row_group, rows_read, e,
+                )))
+            }
+        }
+    }))
+}
+
+/// Call `f` with each row of the row group, up to `limit` rows
+fn for_each_row<F: FnMut(Row)>(
+    parquet_reader: &SerializedFileReader<ParquetFile>,
+    file: &ParquetFile,
+    row_group: usize,
+    limit: Option<usize>,
+    mut f: F,
+) -> Result<(), PQRSError> {
+    let mut rows_read = 0;
+    let mut read = || -> Result<(), ParquetError> {
+        let row_group_reader = parquet_reader.get_row_group(row_group)?;
+        for row in row_group_reader.get_row_iter(None)? {
+            if Some(rows_read) == limit {
+                break;
+            }
+            f(row?);
+            rows_read += 1;
+        }
+        Ok(())
+    };
+
+    read().map_err(|e| {
+        corrupt_row_group(file, parquet_reader.metadata(), row_group, rows_read, e)
+    })
+}
+
+/// The rows of a file as json values, as they are printed by cat --json, which are
+/// read one at a time
+pub struct JsonRows {
+    file: ParquetFile,
+    metadata: ParquetMetaData,
+    renderer: Renderer,
+    rows: RowIter<'static>,
+    row_group: usize,
+    /// The number of rows read from the current row group
+    rows_read: usize,
+}
+
+impl Iterator for JsonRows {
+    type Item = Result<Value, PQRSError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.row_group < self.metadata.num_row_groups()
+            && self.rows_read as i64 == self.metadata.row_group(self.row_group).num_rows()
+        {
+            self.row_group += 1;
+            self.rows_read = 0;
+        }
+        match self.rows.next()? {
+            Ok(row) => {
+                self.rows_read += 1;
+                Some(Ok(self.renderer.render_row_json(&row)))
+            }
+            Err(e) => Some(Err(corrupt_row_group(
+                &self.file,
+                &self.metadata,
+                self.row_group,
+                self.rows_read,
+                e,
+            ))),
+        }
+    }
+}
+
+/// Read the rows of the file as json values, as they are printed by cat --json
+pub fn read_rows_json(file: &ParquetFile) -> Result<JsonRows, PQRSError> {
+    let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
+    let metadata = parquet_reader.metadata().clone();
+    let renderer =
+        Renderer::default().with_schema(metadata.file_metadata().schema_descr());
+
+    Ok(JsonRows {
+        file: file.try_clone()?,
+        metadata,
+        renderer,
+        rows: RowIter::from_file_into(Box::new(parquet_reader)),
+        row_group: 0,
+        rows_read: 0,
+    })
+}
+
+/// How the commands that print records print them
+#[derive(Debug, Clone, Copy)]
+pub struct PrintOptions<'a> {
+    pub format: Formats,
+    pub render: &'a Renderer,
+    /// The number of row groups to decode in parallel
+    pub jobs: usize,
+}
+
+/// Print the given number of records in either json or json-like format.
+/// Up to `jobs` row groups are decoded in parallel when printing all the records.
+/// Row groups that cannot be read are passed to `skipped`.
 pub fn print_rows(
-    file: File,
+    file: ParquetFile,
     num_records: Option<usize>,
-    format: Formats,
+    options: PrintOptions,
+    progress: &mut Progress,
+    skipped: &mut Skipped,
 ) -> Result<(), PQRSError> {
+    let PrintOptions {
+        format,
+        render,
+        jobs,
+    } = options;
     let mut left = num_records;
 
     match format {
         Formats::Default => {
-            let parquet_reader = SerializedFileReader::new(file)?;
-            let mut iter = parquet_reader.get_row_iter(None)?;
-
-            let mut start: usize = 0;
-            let end: usize = num_records.unwrap_or(0);
-            // if num_records is None, print all the files
-            let all_records = num_records.is_none();
-
-            while all_records || start < end {
-                match iter.next() {
-                    Some(row) => {
-                        match row {
-                            Ok(rowval) => print_row(&rowval, format),
-                            Err(_) => todo!(),
+            let parquet_reader = open_row_reader(&file, num_records.is_some())?;
+            let renderer = render
+                .with_schema(parquet_reader.metadata().file_metadata().schema_descr());
+            let row_groups = (0..parquet_reader.num_row_groups()).collect::<Vec<_>>();
+
+            if jobs != 1 && num_records.is_none() {
+                // each row group of a window is rendered by its own thread into a
+                // bounded channel, and the channels are printed in the order of the row
+                // groups, so only a few rendered rows per thread are held at a time
+                let pool = thread_pool(jobs)?;
+                for window in row_groups.chunks(pool.current_num_threads()) {
+                    pool.in_place_scope(|scope| -> Result<(), PQRSError> {
+                        let mut receivers = vec![];
+                        for row_group in window {
+                            let (sender, receiver) = sync_channel(ROWS_IN_FLIGHT);
+                            let (parquet_reader, file, renderer) =
+                                (&parquet_reader, &file, &renderer);
+                            scope.spawn(move |_| {
+                                // sending fails only once printing stopped on an error
+                                let result = for_each_row(
+                                    parquet_reader,
+                                    file,
+                                    *row_group,
+                                    None,
+                                    |row| {
+                                        let _ = sender
+                                            .send(Ok(render_row(&row, format, renderer)));
+                                    },
+                                );
+                                if let Err(e) = result {
+                                    let _ = sender.send(Err(e));
+                                }
+                            });
+                            receivers.push(receiver);
                         }
-                    },
-                    None => break,
+                        for receiver in receivers {
+                            for row in receiver {
+                                match row {
+                                    Ok(row) => {
+                                        println!("{}", row);
+                                        progress.inc(1);
+                                    }
+                                    Err(e) => skipped.skip(e)?,
+                                }
+                            }
+                        }
+                        Ok(())
+                    })?;
+                }
+                return Ok(());
+            }
+
+            for row_group in row_groups {
+                if left == Some(0) {
+                    break;
                 }
-                start += 1;
+                let mut printed = 0;
+                let result =
+                    for_each_row(&parquet_reader, &file, row_group, left, |row| {
+                        print_row(&row, format, &renderer);
+                        progress.inc(1);
+                        printed += 1;
+                    });
+                left = left.map(|l| l - printed);
+                result.or_else(|e| skipped.skip(e))?;
             }
         }
         Formats::Json => {
-            let arrow_reader = ArrowReaderBuilder::try_new(file)?;
-            let batch_reader = arrow_reader.with_batch_size(8192).build()?;
+            let (metadata, batch_reader) = read_batches(file, jobs, num_records)?;
+            let renderer =
+                render.with_schema(metadata.metadata().file_metadata().schema_descr());
             let mut writer = arrow::json::LineDelimitedWriter::new(std::io::stdout());
 
             for maybe_batch in batch_reader {
@@ -102,7 +1003,13 @@ pub fn print_rows(
                     break;
                 }
 
-                let mut batch = maybe_batch?;
+                let mut batch = match maybe_batch {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        skipped.skip(e)?;
+                        continue;
+                    }
+                };
                 if let Some(l) = left {
                     if batch.num_rows() <= l {
                         left = Some(l - batch.num_rows());
@@ -113,14 +1020,16 @@ pub fn print_rows(
                     }
                 };
 
-                writer.write(&batch)?;
+                progress.inc(batch.num_rows() as u64);
+                writer.write(&renderer.render_batch(batch)?)?;
             }
 
             writer.finish()?;
         }
         Formats::Csv => {
-            let arrow_reader = ArrowReaderBuilder::try_new(file)?;
-            let batch_reader = arrow_reader.with_batch_size(8192).build()?;
+            let (metadata, batch_reader) = read_batches(file, jobs, num_records)?;
+            let renderer =
+                render.with_schema(metadata.metadata().file_metadata().schema_descr());
             let mut writer = arrow::csv::Writer::new(std::io::stdout());
 
             for maybe_batch in batch_reader {
@@ -128,7 +1037,13 @@ pub fn print_rows(
                     break;
                 }
 
-                let mut batch = maybe_batch?;
+                let mut batch = match maybe_batch {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        skipped.skip(e)?;
+                        continue;
+                    }
+                };
                 if let Some(l) = left {
                     if batch.num_rows() <= l {
                         left = Some(l - batch.num_rows());
@@ -139,12 +1054,14 @@ pub fn print_rows(
                     }
                 };
 
-                writer.write(&batch)?;
+                progress.inc(batch.num_rows() as u64);
+                writer.write(&renderer.render_batch(batch)?)?;
             }
         }
         Formats::CsvNoHeader => {
-            let arrow_reader = ArrowReaderBuilder::try_new(file)?;
-            let batch_reader = arrow_reader.with_batch_size(8192).build()?;
+            let (metadata, batch_reader) = read_batches(file, jobs, num_records)?;
+            let renderer =
+                render.with_schema(metadata.metadata().file_metadata().schema_descr());
             let writer_builder = arrow::csv::WriterBuilder::new();
             let mut writer = writer_builder.with_header(false).build(std::io::stdout());
 
@@ -153,7 +1070,13 @@ pub fn print_rows(
                     break;
                 }
 
-                let mut batch = maybe_batch?;
+                let mut batch = match maybe_batch {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        skipped.skip(e)?;
+                        continue;
+                    }
+                };
                 if let Some(l) = left {
                     if batch.num_rows() <= l {
                         left = Some(l - batch.num_rows());
@@ -164,24 +1087,30 @@ pub fn print_rows(
                     }
                 };
 
-                writer.write(&batch)?;
+                progress.inc(batch.num_rows() as u64);
+                writer.write(&renderer.render_batch(batch)?)?;
             }
         }
     }
     Ok(())
 }
 
-/// Print the random sample of given size in either json or json-like format
+/// Print the random sample of given size in either json or json-like format.
+/// Row groups that cannot be read are passed to `skipped`.
 pub fn print_rows_random(
-    file: File,
+    file: ParquetFile,
     sample_size: usize,
     format: Formats,
+    render: &Renderer,
+    progress: &mut Progress,
+    skipped: &mut Skipped,
 ) -> Result<(), PQRSError> {
     let parquet_reader = SerializedFileReader::new(file.try_clone()?)?;
-    let iter = parquet_reader.get_row_iter(None)?;
+    let renderer =
+        render.with_schema(parquet_reader.metadata().file_metadata().schema_descr());
 
     // find the number of records present in the file
-    let total_records_in_file: i64 = get_row_count(file)?;
+    let total_records_in_file: i64 = get_row_count(&file, &FooterArgs::default())?;
     // push all the indexes into the vector initially
     let mut indexes = (0..total_records_in_file).collect::<Vec<_>>();
     debug!("Original indexes: {:?}", indexes);
@@ -196,13 +1125,19 @@ pub fn print_rows_random(
 
     debug!("Sampled indexes: {:?}", indexes);
 
-    for (start, row) in (0_i64..).zip(iter) {
-        if indexes.contains(&start) {
-            match row {
-                Ok(rowval) => print_row(&rowval, format),
-                Err(_) => todo!()
+    let mut row_group_start: i64 = 0;
+    for row_group in 0..parquet_reader.num_row_groups() {
+        let mut start = row_group_start;
+        let result = for_each_row(&parquet_reader, &file, row_group, None, |row| {
+            if indexes.contains(&start) {
+                print_row(&row, format, &renderer);
             }
-        }
+            progress.inc(1);
+            start += 1;
+        });
+        result.or_else(|e| skipped.skip(e))?;
+        // the rows after a corrupt row group keep their index in the file
+        row_group_start += parquet_reader.metadata().row_group(row_group).num_rows();
     }
 
     Ok(())
@@ -240,7 +1175,10 @@ impl Add for ParquetData {
 }
 
 /// Return the row batches, rows and schema for a given parquet file
-pub fn get_row_batches(file: File) -> Result<ParquetData, PQRSError> {
+pub fn get_row_batches(
+    file: ParquetFile,
+    progress: &mut Progress,
+) -> Result<ParquetData, PQRSError> {
     let arrow_reader = ArrowReaderBuilder::try_new(file)?;
 
     let schema = Schema::clone(arrow_reader.schema());
@@ -251,6 +1189,7 @@ pub fn get_row_batches(file: File) -> Result<ParquetData, PQRSError> {
     for maybe_batch in record_batch_reader {
         let record_batch = maybe_batch?;
         rows += record_batch.num_rows();
+        progress.inc(record_batch.num_rows() as u64);
 
         batches.push(record_batch);
     }
@@ -263,19 +1202,27 @@ pub fn get_row_batches(file: File) -> Result<ParquetData, PQRSError> {
 }
 
 /// Print the given parquet rows in json or json-like format
-fn print_row(row: &Row, format: Formats) {
+fn print_row(row: &Row, format: Formats, renderer: &Renderer) {
+    println!("{}", render_row(row, format, renderer))
+}
+
+/// Render the given parquet row in json or json-like format
+fn render_row(row: &Row, format: Formats, renderer: &Renderer) -> String {
     match format {
-        Formats::Default => println!("{}", row),
-        Formats::Csv => println!("Unsupported! {}", row),
-        Formats::CsvNoHeader => println!("Unsupported! {}", row),
-        Formats::Json => println!("{}", row.to_json_value()),
+        Formats::Default => renderer.render_row_text(row),
+        Formats::Csv => format!("Unsupported! {}", row),
+        Formats::CsvNoHeader => format!("Unsupported! {}", row),
+        Formats::Json => renderer.render_row_json(row).to_string(),
     }
 }
 
 /// Return the number of rows in the given parquet file
-pub fn get_row_count(file: File) -> Result<i64, PQRSError> {
-    let parquet_reader = SerializedFileReader::new(file)?;
-    let row_group_metadata = parquet_reader.metadata().row_groups();
+pub fn get_row_count(
+    file: &ParquetFile,
+    footer_args: &FooterArgs,
+) -> Result<i64, PQRSError> {
+    let metadata = read_metadata(file, footer_args)?;
+    let row_group_metadata = metadata.row_groups();
     // The parquet file is made up of blocks (also called row groups)
     // The row group metadata contains information about all the row groups present in the data
     // Each row group maintains the number of rows present in the block
@@ -285,49 +1232,269 @@ pub fn get_row_count(file: File) -> Result<i64, PQRSError> {
     Ok(total_num_rows)
 }
 
-/// Return the uncompressed and compressed size of the given file
-pub fn get_size(file: File) -> Result<(i64, i64), PQRSError> {
-    let parquet_reader = SerializedFileReader::new(file)?;
-    let row_group_metadata = parquet_reader.metadata().row_groups();
+/// Return the size of the given file, split by what its bytes hold
+pub fn get_size(file: &ParquetFile, footer: &Footer) -> Result<FileSize, PQRSError> {
+    let row_group_metadata = footer.metadata.row_groups();
 
     // Parquet format compresses data at a column level.
-    // To calculate the size of the file (compressed or uncompressed), we need to sum
+    // To calculate the size of the data (compressed or uncompressed), we need to sum
     // across all the row groups present in the parquet file. This is similar to how
     // we calculate the row count in the method above.
-    // Do note that this size does not take the footer size into consideration.
-    let uncompressed_size = row_group_metadata
-        .iter()
-        .map(|rg| rg.total_byte_size())
-        .sum();
-    let compressed_size = row_group_metadata
-        .iter()
-        .map(|rg| rg.compressed_size())
-        .sum();
+    let mut size = FileSize {
+        file: file.len() as i64,
+        ..Default::default()
+    };
+    for row_group in row_group_metadata {
+        size.uncompressed += row_group.total_byte_size();
+        size.compressed += row_group.compressed_size();
+        for column in row_group.columns() {
+            size.page_index += column.column_index_length().unwrap_or(0) as i64;
+            size.page_index += column.offset_index_length().unwrap_or(0) as i64;
+            size.bloom_filters += bloom_filter_size(file, column)?;
+        }
+    }
+
+    // the footer is the metadata followed by its length and the magic bytes
+    size.footer = (footer.metadata_len + FOOTER_SIZE) as i64;
 
-    Ok((uncompressed_size, compressed_size))
+    Ok(size)
 }
 
-/// Pretty print the given size using human readable format
-pub fn get_pretty_size(bytes: i64) -> String {
-    if bytes / ONE_KI_B < 1 {
-        return format!("{} Bytes", bytes);
+/// The bytes of the bloom filter of the column chunk, including its header. Writers
+/// that do not store the length of the bloom filter only store its offset, in which
+/// case the length is read from the header.
+pub fn bloom_filter_size(
+    file: &ParquetFile,
+    column: &ColumnChunkMetaData,
+) -> Result<i64, PQRSError> {
+    let offset = match column.bloom_filter_offset() {
+        Some(offset) => offset as u64,
+        None => return Ok(0),
+    };
+    if let Some(length) = column.bloom_filter_length() {
+        return Ok(length as i64);
     }
 
-    if bytes / ONE_MI_B < 1 {
-        return format!("{:.3} KiB", bytes / ONE_KI_B);
+    let length =
+        (file.len().saturating_sub(offset) as usize).min(BLOOM_FILTER_HEADER_SIZE);
+    let bytes = file.get_bytes(offset, length)?;
+    let (header, header_length) = read_thrift::<BloomFilterHeader>(&bytes)?;
+    Ok(header_length as i64 + header.num_bytes as i64)
+}
+
+/// The bytes of a Parquet file by what they hold
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FileSize {
+    /// The uncompressed bytes of the column chunks
+    pub uncompressed: i64,
+    /// The bytes of the column chunks as stored, including the page headers
+    pub compressed: i64,
+    /// The bytes of the whole file
+    pub file: i64,
+    /// The file metadata, its length and the magic bytes after it
+    pub footer: i64,
+    /// The column and offset indexes
+    pub page_index: i64,
+    pub bloom_filters: i64,
+}
+
+impl FileSize {
+    /// The bytes that are not part of anything else, e.g. the magic bytes at the start
+    /// of the file and the copies of the column metadata that some writers put after
+    /// each column chunk
+    pub fn other(&self) -> i64 {
+        self.file - self.compressed - self.footer - self.page_index - self.bloom_filters
     }
+}
 
-    if bytes / ONE_GI_B < 1 {
-        return format!("{:.3} MiB", bytes / ONE_MI_B);
+impl AddAssign for FileSize {
+    fn add_assign(&mut self, rhs: FileSize) {
+        self.uncompressed += rhs.uncompressed;
+        self.compressed += rhs.compressed;
+        self.file += rhs.file;
+        self.footer += rhs.footer;
+        self.page_index += rhs.page_index;
+        self.bloom_filters += rhs.bloom_filters;
     }
+}
+
+/// A unit to print sizes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeUnit {
+    bytes: i64,
+    symbol: &'static str,
+}
 
-    if bytes / ONE_TI_B < 1 {
-        return format!("{:.3} GiB", bytes / ONE_GI_B);
+impl SizeUnit {
+    const fn new(bytes: i64, symbol: &'static str) -> SizeUnit {
+        SizeUnit { bytes, symbol }
     }
 
-    if bytes / ONE_PI_B < 1 {
-        return format!("{:.3} TiB", bytes / ONE_TI_B);
+    /// Format the size in this unit, whole bytes without decimals
+    fn format(&self, bytes: i64) -> String {
+        if self.bytes == 1 {
+            format!("{} Bytes", bytes)
+        } else {
+            format!("{:.3} {}", bytes as f64 / self.bytes as f64, self.symbol)
+        }
     }
+}
+
+impl FromStr for SizeUnit {
+    type Err = String;
+
+    /// Parse a unit symbol regardless of its case, e.g. MiB, mib or MB
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        IEC_UNITS
+            .iter()
+            .chain(SI_UNITS.iter().skip(1))
+            .find(|unit| unit.symbol.eq_ignore_ascii_case(symbol))
+            .copied()
+            .ok_or_else(|| {
+                let symbols = IEC_UNITS
+                    .iter()
+                    .chain(SI_UNITS.iter().skip(1))
+                    .map(|unit| unit.symbol)
+                    .collect::<Vec<_>>();
+                format!("unknown unit, expected one of {}", symbols.join(", "))
+            })
+    }
+}
+
+/// Options for how the commands that print sizes format them
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct SizeFormatArgs {
+    /// Show pretty, human readable size
+    #[arg(short, long)]
+    pub pretty: bool,
+
+    /// Use SI units for pretty sizes, which are powers of 1000 (kB, MB, ...) instead of
+    /// powers of 1024 (KiB, MiB, ...)
+    #[arg(long, conflicts_with = "unit")]
+    pub si: bool,
+
+    /// Show all sizes in the given unit, e.g. B, KiB or MB, which implies --pretty
+    #[arg(long, value_name = "UNIT")]
+    pub unit: Option<SizeUnit>,
+}
+
+impl SizeFormatArgs {
+    /// Format the size in bytes as asked for, as a plain number by default
+    pub fn format(&self, bytes: i64) -> String {
+        match self.unit {
+            Some(unit) => unit.format(bytes),
+            None if self.pretty => get_pretty_size(bytes, self.si),
+            None => bytes.to_string(),
+        }
+    }
+}
+
+/// Pretty print the given size using human readable format, in the largest unit that
+/// the size is at least one of
+pub fn get_pretty_size(bytes: i64, si: bool) -> String {
+    let units = if si { &SI_UNITS } else { &IEC_UNITS };
+    units
+        .iter()
+        .rev()
+        .find(|unit| bytes.abs() >= unit.bytes)
+        .unwrap_or(&units[0])
+        .format(bytes)
+}
+
+/// Print the rows as a table, the first row being the header. The cells of the columns
+/// marked as numeric are aligned to the right, the others to the left.
+pub fn print_table(rows: &[Vec<String>], numeric: &[bool]) {
+    let mut widths = vec![0; numeric.len()];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in rows {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .zip(numeric)
+            .map(|((cell, &width), &numeric)| {
+                if numeric {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
 
-    format!("{:.3} PiB", bytes / ONE_PI_B)
+/// Display a value of statistics or a page index as text, or as hex when it is not
+/// valid UTF-8
+pub fn format_plain_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("0x{}", format_binary(bytes, BinaryFormat::Hex)),
+    }
+}
+
+/// Display a single value in its plain encoding, as the values in a column index are
+/// stored, without applying the logical type of the column
+pub fn format_plain_value(physical_type: PhysicalType, bytes: &[u8]) -> String {
+    let value = match physical_type {
+        PhysicalType::BOOLEAN => bytes.first().map(|b| (*b != 0).to_string()),
+        PhysicalType::INT32 => bytes
+            .try_into()
+            .ok()
+            .map(|b| i32::from_le_bytes(b).to_string()),
+        PhysicalType::INT64 => bytes
+            .try_into()
+            .ok()
+            .map(|b| i64::from_le_bytes(b).to_string()),
+        PhysicalType::FLOAT => bytes
+            .try_into()
+            .ok()
+            .map(|b| f32::from_le_bytes(b).to_string()),
+        PhysicalType::DOUBLE => bytes
+            .try_into()
+            .ok()
+            .map(|b| f64::from_le_bytes(b).to_string()),
+        PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+            Some(format_plain_bytes(bytes))
+        }
+        PhysicalType::INT96 => None,
+    };
+    value.unwrap_or_else(|| format!("0x{}", format_binary(bytes, BinaryFormat::Hex)))
+}
+
+/// The name of an encoding in a page header, which may be one this version of the
+/// parquet crate does not know about
+pub fn encoding_name(encoding: format::Encoding) -> String {
+    match Encoding::try_from(encoding) {
+        Ok(encoding) => encoding.to_string(),
+        Err(_) => format!("UNKNOWN({})", encoding.0),
+    }
+}
+
+/// The minimum and maximum of the statistics as they are stored, without applying the
+/// logical type of the column
+pub fn format_min_max(statistics: &Statistics) -> Option<(String, String)> {
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+    let min_max = match statistics {
+        Statistics::Boolean(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::Int32(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::Int64(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::Int96(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::Float(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::Double(s) => (s.min().to_string(), s.max().to_string()),
+        Statistics::ByteArray(s) => (
+            format_plain_bytes(s.min().data()),
+            format_plain_bytes(s.max().data()),
+        ),
+        Statistics::FixedLenByteArray(s) => (
+            format_plain_bytes(s.min().data()),
+            format_plain_bytes(s.max().data()),
+        ),
+    };
+    Some(min_max)
 }
@@ -2,7 +2,23 @@ static SIMPLE_PARQUET_PATH: &str = "data/simple.parquet";
 static CITIES_PARQUET_PATH: &str = "data/cities.parquet";
 static PEMS_1_PARQUET_PATH: &str = "data/pems-1.snappy.parquet";
 static PEMS_2_PARQUET_PATH: &str = "data/pems-2.snappy.parquet";
+static TYPES_PARQUET_PATH: &str = "data/types.parquet";
+// the second of the three records of its list column starts in one page and ends in
+// the next
+static SPANNING_RECORDS_PARQUET_PATH: &str = "data/spanning-records.parquet";
+// a nanosecond timestamp, a 16 byte field without the UUID type and a list of UUIDs
+static NESTED_TYPES_PARQUET_PATH: &str = "data/nested-types.parquet";
+static PEMS_INDEXED_PARQUET_PATH: &str = "data/pems-indexed.snappy.parquet";
+static CHECKSUMS_PARQUET_PATH: &str = "data/checksums.parquet";
+static SCORES_1_PARQUET_PATH: &str = "data/scores-1.parquet";
+static SCORES_2_PARQUET_PATH: &str = "data/scores-2.parquet";
+// the dictionary of its word column outgrew the size limit after three data pages
+static FALLBACK_PARQUET_PATH: &str = "data/fallback.parquet";
 static MERGED_FILE_NAME: &str = "merged.snappy.parquet";
+/// The first data page of column timeperiod in row group 1 of PEMS_INDEXED_PARQUET_PATH
+static CORRUPT_PAGE_OFFSET: usize = 16204;
+// a byte in the body of the first page of data/checksums.parquet
+static CHECKSUMMED_PAGE_OFFSET: usize = 30;
 static CAT_OUTPUT: &str = r#"{continent: "Europe", country: {name: "France", city: ["Paris", "Nice", "Marseilles", "Cannes"]}}
 {continent: "Europe", country: {name: "Greece", city: ["Athens", "Piraeus", "Hania", "Heraklion", "Rethymnon", "Fira"]}}
 {continent: "North America", country: {name: "Canada", city: ["Toronto", "Vancouver", "St. John's", "Saint John", "Montreal", "Halifax", "Winnipeg", "Calgary", "Saskatoon", "Ottawa", "Yellowknife"]}}
@@ -30,14 +46,129 @@ static SCHEMA_OUTPUT: &str = r#"message hive_schema {
 static SAMPLE_PARTIAL_OUTPUT_1: &str = "{continent:";
 static SAMPLE_PARTIAL_OUTPUT_2: &str = "country: {name:";
 
+/// A minimal HTTP server standing in for an object store, which serves the files in the
+/// data directory and only answers ranged GET requests, so whole file downloads fail
+mod object_store_stub {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Counters of what the server was asked for
+    #[derive(Default)]
+    struct Served {
+        /// The number of body bytes served
+        bytes: AtomicUsize,
+        /// The number of HEAD requests
+        heads: AtomicUsize,
+    }
+
+    /// A running server, which stops with the test process
+    pub struct Server {
+        address: String,
+        served: Arc<Served>,
+    }
+
+    impl Server {
+        /// The URL of a file in the data directory
+        pub fn url(&self, file_name: &str) -> String {
+            format!("{}/{}", self.address, file_name)
+        }
+
+        /// The number of body bytes served so far
+        pub fn bytes(&self) -> usize {
+            self.served.bytes.load(Ordering::SeqCst)
+        }
+
+        /// The number of HEAD requests answered so far
+        pub fn heads(&self) -> usize {
+            self.served.heads.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Start the server in the background
+    pub fn serve() -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let served = Arc::new(Served::default());
+        let counter = served.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let counter = counter.clone();
+                thread::spawn(move || handle(stream, counter));
+            }
+        });
+        Server { address, served }
+    }
+
+    fn handle(stream: TcpStream, served: Arc<Served>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut stream = stream;
+        loop {
+            let mut request = String::new();
+            if reader.read_line(&mut request).unwrap_or(0) == 0 {
+                return;
+            }
+            let mut range = None;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some(value) =
+                    header.to_ascii_lowercase().strip_prefix("range: bytes=")
+                {
+                    let (start, end) = value.split_once('-').unwrap();
+                    range = Some((
+                        start.parse::<usize>().unwrap(),
+                        end.parse::<usize>().unwrap(),
+                    ));
+                }
+            }
+
+            let parts: Vec<&str> = request.split_whitespace().collect();
+            let path = format!("data{}", parts[1]);
+            let response = match (std::fs::read(path), parts[0], range) {
+                (Err(_), _, _) => {
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec()
+                }
+                (Ok(data), "HEAD", _) => {
+                    served.heads.fetch_add(1, Ordering::SeqCst);
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", data.len())
+                        .into_bytes()
+                }
+                (Ok(data), "GET", Some((start, end))) => {
+                    let body = &data[start..=end];
+                    served.bytes.fetch_add(body.len(), Ordering::SeqCst);
+                    let mut response = format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+                        body.len(), start, end, data.len()
+                    ).into_bytes();
+                    response.extend_from_slice(body);
+                    response
+                }
+                _ => b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            };
+            stream.write_all(&response).unwrap();
+        }
+    }
+}
+
 /// Integration tests for the crate
 mod integration {
     // make sure any new commands added have a corresponding integration test here!
+    use crate::object_store_stub;
     use crate::{
         CAT_CSV_NO_HEADER_OUTPUT, CAT_CSV_OUTPUT, CAT_JSON_OUTPUT, CAT_OUTPUT,
-        CITIES_PARQUET_PATH, MERGED_FILE_NAME, PEMS_1_PARQUET_PATH, PEMS_2_PARQUET_PATH,
-        SAMPLE_PARTIAL_OUTPUT_1, SAMPLE_PARTIAL_OUTPUT_2, SCHEMA_OUTPUT,
-        SIMPLE_PARQUET_PATH,
+        CHECKSUMMED_PAGE_OFFSET, CHECKSUMS_PARQUET_PATH, CITIES_PARQUET_PATH,
+        CORRUPT_PAGE_OFFSET, FALLBACK_PARQUET_PATH, MERGED_FILE_NAME,
+        NESTED_TYPES_PARQUET_PATH, PEMS_1_PARQUET_PATH, PEMS_2_PARQUET_PATH,
+        PEMS_INDEXED_PARQUET_PATH, SAMPLE_PARTIAL_OUTPUT_1, SAMPLE_PARTIAL_OUTPUT_2,
+        SCHEMA_OUTPUT, SCORES_1_PARQUET_PATH, SCORES_2_PARQUET_PATH, SIMPLE_PARQUET_PATH,
+        SPANNING_RECORDS_PARQUET_PATH, TYPES_PARQUET_PATH,
     };
     use assert_cmd::Command;
     use predicates::prelude::*;
@@ -106,6 +237,252 @@ mod integration {
         Ok(())
     }
 
+    #[test]
+    fn validate_cat_quiet() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--quiet")
+            .arg("--progress")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::starts_with(CAT_OUTPUT))
+            .stderr(predicate::str::is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_missing_file() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat").arg("data/missing.parquet");
+        cmd.assert().failure().code(3).stderr(predicate::str::diff(
+            "Error: File data/missing.parquet not found, please check if it exists\n",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_error_format_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("rowcount")
+            .arg("--error-format")
+            .arg("json")
+            .arg("README.md");
+        let output = cmd.assert().failure().code(5).get_output().stderr.clone();
+
+        let report: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(report["error"]["category"], "invalid_data");
+        assert_eq!(report["error"]["exit_code"], 5);
+        assert_eq!(report["error"]["file"], "README.md");
+        assert_eq!(report["error"]["message"], "Could not read Parquet File");
+        assert!(report["error"]["causes"][0]
+            .as_str()
+            .unwrap()
+            .contains("Corrupt footer"));
+
+        // command line errors are reported in the same form
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("rowcount")
+            .arg("--error-format=json")
+            .arg("--no-such-option")
+            .arg(SIMPLE_PARQUET_PATH);
+        let output = cmd.assert().failure().code(2).get_output().stderr.clone();
+
+        let report: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(report["error"]["category"], "usage");
+        assert_eq!(report["error"]["exit_code"], 2);
+        assert_eq!(
+            report["error"]["message"],
+            "unexpected argument '--no-such-option' found"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_footer_errors_name_the_file() -> Result<(), Box<dyn std::error::Error>> {
+        for command in [
+            "bloom",
+            "dictionary",
+            "layout",
+            "page-index",
+            "pages",
+            "row-groups",
+            "size",
+        ] {
+            let mut cmd = Command::cargo_bin("pqrs")?;
+            cmd.arg(command).arg("README.md");
+            cmd.assert().failure().code(5).stdout("").stderr(
+                predicate::str::starts_with(
+                    "Error: README.md: Could not read Parquet File",
+                ),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_skip_corrupt() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let corrupt_path = dir.path().join("corrupt.parquet");
+        let mut bytes = std::fs::read(PEMS_INDEXED_PARQUET_PATH)?;
+        bytes[CORRUPT_PAGE_OFFSET..CORRUPT_PAGE_OFFSET + 64].fill(0xff);
+        std::fs::write(&corrupt_path, bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat").arg("--json").arg(&corrupt_path);
+        cmd.assert().failure().code(5).stderr(
+            predicate::str::contains("row group 1, column timeperiod")
+                .and(predicate::str::contains("Caused by: ")),
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--json")
+            .arg("--skip-corrupt")
+            .arg(&corrupt_path);
+        let output = cmd
+            .assert()
+            .success()
+            .stderr(
+                predicate::str::contains("row group 1, column timeperiod").and(
+                    predicate::str::contains(
+                        "Skipped 1000 rows in 1 row groups that could not be read",
+                    ),
+                ),
+            )
+            .get_output()
+            .stdout
+            .clone();
+        assert_eq!(String::from_utf8(output)?.lines().count(), 1693);
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("sample")
+            .arg("--records")
+            .arg("5")
+            .arg("--skip-corrupt")
+            .arg(&corrupt_path);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Skipped 1000 rows"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_rendering() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg(TYPES_PARQUET_PATH)
+            .arg("--binary")
+            .arg("hex")
+            .arg("--timezone")
+            .arg("+01:00");
+        cmd.assert().success().stdout(
+            predicate::str::contains(r#"payload: "deadbeef""#)
+                .and(predicate::str::contains(
+                    r#"uid: "00010203-0405-0607-0809-0a0b0c0d0e0f""#,
+                ))
+                .and(predicate::str::contains("price: 123.45"))
+                .and(predicate::str::contains(
+                    "ts: 2021-03-04T06:06:07.123456+01:00",
+                )),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_rendering_by_column_type() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat").arg("--quiet").arg(NESTED_TYPES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::starts_with(
+            "{id: 1, ts_ns: 2021-03-04 05:06:07 +00:00, legacy_ts: 2021-03-04 05:06:07 +00:00, \
+             ref: {uid: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]}, \
+             uids: [\"10111213-1415-1617-1819-1a1b1c1d1e1f\", \
+             \"20212223-2425-2627-2829-2a2b2c2d2e2f\"]}\n",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--quiet")
+            .arg("--timezone")
+            .arg("+01:00")
+            .arg("--timestamp-format")
+            .arg("%H:%M:%S%.9f")
+            .arg(NESTED_TYPES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::starts_with(
+            "{id: 1, ts_ns: 06:06:07.123456789, legacy_ts: 06:06:07.123000000,",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--quiet")
+            .arg("--json")
+            .arg("--epoch-unit")
+            .arg("ns")
+            .arg(NESTED_TYPES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::starts_with(
+            "{\"id\":1,\"ts_ns\":1614834367123456789,\"legacy_ts\":1614834367123456789,\
+             \"ref\":{\"uid\":\"AAECAwQFBgcICQoLDA0ODw==\"},",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_invalid_timezone() -> Result<(), Box<dyn std::error::Error>> {
+        // the options are checked before looking for the files
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--timezone")
+            .arg("Nowhere/City")
+            .arg("data/missing.parquet");
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr("Error: Invalid rendering option: invalid timezone: Nowhere/City\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_json_rendering() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg(TYPES_PARQUET_PATH)
+            .arg("--json")
+            .arg("--binary")
+            .arg("hex");
+        cmd.assert().success().stdout(
+            predicate::str::contains(r#""payload":"deadbeef""#)
+                .and(predicate::str::contains(
+                    r#""uid":"550e8400-e29b-41d4-a716-446655440000""#,
+                ))
+                .and(predicate::str::contains(r#""price":"-0.05""#)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cat_csv_rendering() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg(TYPES_PARQUET_PATH)
+            .arg("--csv")
+            .arg("--epoch-unit")
+            .arg("ms");
+        cmd.assert().success().stdout(predicate::str::contains(
+            "1,3q2+7w==,00010203-0405-0607-0809-0a0b0c0d0e0f,123.45,1614834367123,1614834367000",
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn validate_head() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
@@ -118,6 +495,74 @@ mod integration {
         Ok(())
     }
 
+    #[test]
+    fn validate_head_page_index() -> Result<(), Box<dyn std::error::Error>> {
+        // the first row group has pages of 250 rows, so this ends in the middle of a page
+        for format in ["--json", "--csv"] {
+            let mut cmd = Command::cargo_bin("pqrs")?;
+            cmd.arg("cat").arg(format).arg(PEMS_INDEXED_PARQUET_PATH);
+            let all = cmd.assert().success().get_output().stdout.clone();
+
+            let mut cmd = Command::cargo_bin("pqrs")?;
+            cmd.arg("head")
+                .arg(format)
+                .arg("-n")
+                .arg("260")
+                .arg(PEMS_INDEXED_PARQUET_PATH);
+            let head = cmd.assert().success().get_output().stdout.clone();
+
+            let lines = if format == "--csv" { 261 } else { 260 };
+            let expected = String::from_utf8(all)?
+                .lines()
+                .take(lines)
+                .map(|line| format!("{}\n", line))
+                .collect::<String>();
+            assert_eq!(String::from_utf8(head)?, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_layout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("layout").arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "File Name: data/cities.parquet\n\
+             Offset  Length  Region           Row Group  Column\n     \
+             0       4  magic                    -  -\n     \
+             4      40  dictionary_page          0  continent\n    \
+             44      53  data_page_v1             0  continent\n    \
+             97      74  data_page_v1             0  country.name\n   \
+             171     299  data_page_v1             0  country.city.bag.array_element\n   \
+             470     388  footer                   -  -\n   \
+             858       4  footer_length            -  -\n   \
+             862       4  magic                    -  -\n\
+             Gaps: 0\n\
+             Gap Size: 0\n",
+        );
+
+        // the writer of this file puts a copy of the column metadata after each chunk
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("layout")
+            .arg("--chunks")
+            .arg("--json")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "{\"offset\":6195,\"length\":84,\"kind\":\"gap\"}",
+            ))
+            .stdout(predicate::str::contains(
+                "\"kind\":\"bloom_filter\",\"row_group\":0,\"column\":\"timeperiod\"",
+            ))
+            .stdout(predicate::str::contains(
+                "\"gaps\":75,\"gap_bytes\":3897,\"overlaps\":0",
+            ));
+
+        Ok(())
+    }
+
     #[test]
     fn validate_merge() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
@@ -143,6 +588,43 @@ mod integration {
         Ok(())
     }
 
+    #[test]
+    fn validate_merge_stdout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("merge")
+            .arg("--input")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(PEMS_2_PARQUET_PATH)
+            .arg("--output")
+            .arg("-");
+        let merged = cmd.assert().success().get_output().stdout.clone();
+
+        let mut rowcount_cmd = Command::cargo_bin("pqrs")?;
+        rowcount_cmd.arg("rowcount").arg("-").write_stdin(merged);
+        rowcount_cmd
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("5573 rows"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_head_stdin() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        let lines: Vec<&str> = CAT_OUTPUT.split('\n').collect();
+        cmd.arg("head")
+            .arg("-")
+            .arg("-n")
+            .arg("1")
+            .write_stdin(std::fs::read(CITIES_PARQUET_PATH)?);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(lines[0]));
+
+        Ok(())
+    }
+
     #[test]
     fn validate_rowcount() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
@@ -155,104 +637,1133 @@ mod integration {
     }
 
     #[test]
-    fn validate_sample() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_cat_parallel() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("sample")
-            .arg(CITIES_PARQUET_PATH)
-            .arg("--records")
-            .arg("1");
-        cmd.assert().success().stdout(
-            predicate::str::contains(SAMPLE_PARTIAL_OUTPUT_2)
-                .and(predicate::str::starts_with(SAMPLE_PARTIAL_OUTPUT_1)),
+        cmd.arg("cat")
+            .arg("--jobs")
+            .arg("1")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        let sequential = cmd.assert().success().get_output().stdout.clone();
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--jobs")
+            .arg("3")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        let parallel = cmd.assert().success().get_output().stdout.clone();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(
+            String::from_utf8(parallel)?
+                .lines()
+                .filter(|l| l.starts_with('{'))
+                .count(),
+            2693
         );
 
         Ok(())
     }
 
     #[test]
-    fn validate_schema() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_rowcount_parallel() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("schema").arg(CITIES_PARQUET_PATH);
-        cmd.assert()
-            .success()
-            .stdout(predicate::str::contains(SCHEMA_OUTPUT));
+        cmd.arg("rowcount")
+            .arg("--jobs")
+            .arg("0")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(PEMS_2_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::diff(
+            "File Name: data/pems-1.snappy.parquet: 2693 rows\n\
+             File Name: data/pems-2.snappy.parquet: 2880 rows\n\
+             File Name: data/cities.parquet: 3 rows\n\
+             Total: 5576 rows\n",
+        ));
 
         Ok(())
     }
 
     #[test]
-    fn validate_schema_json() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_rowcount_json_and_csv() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("schema").arg("--json").arg(CITIES_PARQUET_PATH);
+        cmd.arg("rowcount")
+            .arg("--json")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
         cmd.assert().success().stdout(
-            predicate::str::contains("version").and(
-                predicate::str::contains("num_rows")
-                    .and(predicate::str::contains("created_by"))
-                    .and(predicate::str::contains("message")),
-            ),
+            "{\"files\":[{\"file\":\"data/pems-1.snappy.parquet\",\"rows\":2693},\
+             {\"file\":\"data/cities.parquet\",\"rows\":3}],\
+             \"total\":{\"files\":2,\"file\":\"total\",\"rows\":2696}}\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("rowcount")
+            .arg("--csv")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "file,rows\n\
+             data/pems-1.snappy.parquet,2693\n\
+             data/cities.parquet,3\n\
+             total,2696\n",
         );
 
         Ok(())
     }
 
     #[test]
-    fn validate_schema_arrow() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_row_groups() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("schema").arg("--arrow").arg(CITIES_PARQUET_PATH);
+        cmd.arg("rowgroups")
+            .arg("--column")
+            .arg("timeperiod")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
         cmd.assert().success().stdout(
-            predicate::str::contains("\"fields\": [")
-            .and(predicate::str::contains("\"name\": \"continent\","))
-            .and(predicate::str::contains("\"name\": \"country\","))
+            "File Name: data/pems-indexed.snappy.parquet\n\
+             Row Group  Ordinal  Offset  Rows  Uncompressed  Compressed  Sorted By  timeperiod Min       timeperiod Max\n        \
+             0        0       4  1000         28234       10148  -          01/17/2016 00:00:27  01/17/2016 08:19:57\n        \
+             1        1   11451  1000         28234       10214  -          01/17/2016 08:20:27  01/17/2016 17:50:27\n        \
+             2        2   22964   693         19874        7415  -          01/17/2016 17:50:57  01/17/2016 23:59:57\n",
         );
 
-        // TODO: validate that the stdout is parseable json and can be read by the arrow libs
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("row-groups")
+            .arg("--json")
+            .arg("--column")
+            .arg("name")
+            .arg(CHECKSUMS_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::contains(
+            "{\"row_group\":1,\"ordinal\":1,\"file_offset\":417,\"num_rows\":50,\
+             \"total_byte_size\":602,\"compressed_size\":415,\"sorting_columns\":[],\
+             \"columns\":[{\"column\":\"name\",\"min\":\"name-0\",\"max\":\"name-6\",\
+             \"null_count\":0}]}",
+        ));
 
         Ok(())
     }
 
     #[test]
-    fn validate_uncompressed_size() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_rowcount_verbose_io() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("size").arg(PEMS_1_PARQUET_PATH);
+        cmd.arg("rowcount")
+            .arg("--verbose-io")
+            .arg(PEMS_1_PARQUET_PATH);
         cmd.assert()
             .success()
-            .stdout(predicate::str::contains("Uncompressed Size: 63085"));
+            .stdout(predicate::str::contains("2693 rows"))
+            .stderr(predicate::str::contains("3397 bytes read, 2 seeks"));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--verbose-io")
+            .arg("--prefetch")
+            .arg("8192")
+            .arg(PEMS_1_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("8192 bytes read, 1 seeks"));
 
         Ok(())
     }
 
     #[test]
-    fn validate_uncompressed_size_pretty() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_rowcount_remote() -> Result<(), Box<dyn std::error::Error>> {
+        let server = object_store_stub::serve();
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("size").arg(PEMS_1_PARQUET_PATH).arg("--pretty");
+        cmd.arg("rowcount").arg(server.url("pems-1.snappy.parquet"));
         cmd.assert()
             .success()
-            .stdout(predicate::str::contains("Uncompressed Size: 61 KiB"));
+            .stdout(predicate::str::contains("2693 rows"));
+
+        // only the footer should have been transferred
+        let file_size = std::fs::metadata(PEMS_1_PARQUET_PATH)?.len() as usize;
+        assert!(server.bytes() < file_size / 2);
+        // checking that the file exists and opening it share the same lookup
+        assert_eq!(server.heads(), 1);
 
         Ok(())
     }
 
     #[test]
-    fn validate_compressed_size() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_cat_remote() -> Result<(), Box<dyn std::error::Error>> {
+        let server = object_store_stub::serve();
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("size").arg(PEMS_1_PARQUET_PATH).arg("--compressed");
+        cmd.arg("cat").arg(server.url("cities.parquet"));
         cmd.assert()
             .success()
-            .stdout(predicate::str::contains("Compressed Size: 13067"));
+            .stdout(predicate::str::contains(CAT_OUTPUT));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--json")
+            .arg(server.url("cities.parquet"));
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(CAT_JSON_OUTPUT));
 
         Ok(())
     }
 
     #[test]
-    fn validate_compressed_size_pretty() -> Result<(), Box<dyn std::error::Error>> {
+    fn validate_sample() -> Result<(), Box<dyn std::error::Error>> {
         let mut cmd = Command::cargo_bin("pqrs")?;
-        cmd.arg("size")
-            .arg(PEMS_1_PARQUET_PATH)
-            .arg("--compressed")
-            .arg("--pretty");
+        cmd.arg("sample")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("--records")
+            .arg("1");
+        cmd.assert().success().stdout(
+            predicate::str::contains(SAMPLE_PARTIAL_OUTPUT_2)
+                .and(predicate::str::starts_with(SAMPLE_PARTIAL_OUTPUT_1)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema").arg(CITIES_PARQUET_PATH);
         cmd.assert()
             .success()
-            .stdout(predicate::str::contains("Compressed Size: 12 KiB"));
+            .stdout(predicate::str::contains(SCHEMA_OUTPUT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema").arg("--json").arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains("version").and(
+                predicate::str::contains("num_rows")
+                    .and(predicate::str::contains("created_by"))
+                    .and(predicate::str::contains("message")),
+            ),
+        );
+
+        let output = cmd.output()?.stdout;
+        let schema: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(schema["format_version"], 2);
+        let city = &schema["schema"]["fields"][1]["fields"][1];
+        assert_eq!(city["kind"], "group");
+        assert_eq!(city["converted_type"], "LIST");
+        assert_eq!(city["fields"][0]["repetition"], "REPEATED");
+        let column = &schema["columns"][2];
+        assert_eq!(
+            column["path"],
+            serde_json::json!(["country", "city", "bag", "array_element"])
+        );
+        assert_eq!(column["max_definition_level"], 4);
+        assert_eq!(column["max_repetition_level"], 1);
+        assert_eq!(column["compressions"], serde_json::json!(["UNCOMPRESSED"]));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema").arg("--json").arg(TYPES_PARQUET_PATH);
+        let output = cmd.output()?.stdout;
+        let schema: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(
+            schema["schema"]["fields"][3]["logical_type"],
+            serde_json::json!({"type": "DECIMAL", "precision": 10, "scale": 2})
+        );
+        assert_eq!(
+            schema["columns"][4]["logical_type"],
+            serde_json::json!({"type": "TIMESTAMP", "unit": "MICROS", "adjusted_to_utc": true})
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_arrow() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema").arg("--arrow").arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains("\"fields\": [")
+                .and(predicate::str::contains("\"name\": \"continent\","))
+                .and(predicate::str::contains("\"name\": \"country\",")),
+        );
+
+        // TODO: validate that the stdout is parseable json and can be read by the arrow libs
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_as() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("sql")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "CREATE TABLE \"cities\" (\n  \
+             \"continent\" VARCHAR,\n  \
+             \"country\" ROW(\"name\" VARCHAR, \"city\" VARCHAR ARRAY)\n\
+             );\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("hive")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::contains(
+            "`country` STRUCT<`name`:STRING,`city`:ARRAY<STRING>>\n)\nSTORED AS PARQUET;",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("protobuf")
+            .arg(TYPES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "syntax = \"proto3\";\n\n\
+             import \"google/protobuf/timestamp.proto\";\n\
+             import \"google/type/decimal.proto\";\n\n\
+             message Types {\n  \
+             int64 id = 1;\n  \
+             optional bytes payload = 2;\n  \
+             optional bytes uid = 3;\n  \
+             google.type.Decimal price = 4;\n  \
+             google.protobuf.Timestamp ts = 5;\n  \
+             google.protobuf.Timestamp legacy_ts = 6;\n\
+             }\n\n",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_as_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("avro")
+            .arg(TYPES_PARQUET_PATH);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let avro: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(avro["name"], "types");
+        assert_eq!(avro["fields"][0]["type"], "long");
+        assert_eq!(avro["fields"][3]["type"][1]["logicalType"], "decimal");
+        assert_eq!(
+            avro["fields"][4]["type"][1]["logicalType"],
+            "timestamp-micros"
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("bigquery")
+            .arg(CITIES_PARQUET_PATH);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let bigquery: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(bigquery[1]["type"], "RECORD");
+        assert_eq!(bigquery[1]["fields"][1]["mode"], "REPEATED");
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema")
+            .arg("--as")
+            .arg("spark")
+            .arg("--json")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().failure().code(2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_uncompressed_size() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size").arg(PEMS_1_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Uncompressed Size: 63085"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_uncompressed_size_pretty() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size").arg(PEMS_1_PARQUET_PATH).arg("--pretty");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Uncompressed Size: 61.606 KiB"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_compressed_size() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size").arg(PEMS_1_PARQUET_PATH).arg("--compressed");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Compressed Size: 13067"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_compressed_size_pretty() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--compressed")
+            .arg("--pretty");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Compressed Size: 12.761 KiB"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_units() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--pretty")
+            .arg("--si");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Uncompressed Size: 63.085 kB"))
+            .stdout(predicate::str::contains("Other: 4 Bytes"));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--unit")
+            .arg("kib");
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Uncompressed Size: 61.606 KiB"))
+            .stdout(predicate::str::contains("Other: 0.004 KiB"));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg("--unit")
+            .arg("XB");
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("unknown unit"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_on_disk() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::ends_with(
+            "File Name: data/cities.parquet\n\
+             Uncompressed Size: 466\n\
+             File Size: 866\n  \
+             Page Index: 0\n  \
+             Bloom Filters: 0\n  \
+             Footer: 396\n  \
+             Other: 4\n\n\
+             Total (2 files):\n\
+             Uncompressed Size: 76808\n\
+             File Size: 53748\n  \
+             Page Index: 7038\n  \
+             Bloom Filters: 6192\n  \
+             Footer: 8370\n  \
+             Other: 3905\n",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_compressed_and_uncompressed(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg("--compressed")
+            .arg("--uncompressed")
+            .arg(PEMS_1_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::contains(
+            "Uncompressed Size: 63085\n\
+             Compressed Size: 13067\n\
+             File Size: 16468\n",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_json_and_csv() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size").arg("--json").arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "{\"files\":[{\"file\":\"data/cities.parquet\",\"uncompressed\":466,\
+             \"compressed\":466,\"file_size\":866,\"page_index\":0,\
+             \"bloom_filters\":0,\"footer\":396,\"other\":4}],\
+             \"total\":{\"files\":1,\"file\":\"total\",\"uncompressed\":466,\
+             \"compressed\":466,\"file_size\":866,\"page_index\":0,\
+             \"bloom_filters\":0,\"footer\":396,\"other\":4}}\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg("--csv")
+            .arg(PEMS_1_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "file,uncompressed,compressed,file_size,page_index,bloom_filters,footer,other\n\
+             data/pems-1.snappy.parquet,63085,13067,16468,0,0,3397,4\n\
+             data/cities.parquet,466,466,866,0,0,396,4\n\
+             total,63551,13533,17334,0,0,3793,8\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg("--csv")
+            .arg("--by")
+            .arg("column")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_by_column() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg("--by")
+            .arg("column")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "Size in Bytes:\n\n\
+             File Name: data/cities.parquet\n\
+             Column                          Compressed  Uncompressed  Ratio  % of File  Dictionary  Data\n\
+             continent                               93            93  1.00x      10.7%          40    53\n\
+             country.name                            74            74  1.00x       8.5%           0    74\n\
+             country.city.bag.array_element         299           299  1.00x      34.5%           0   299\n",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_size_by_row_group() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("size")
+            .arg("--by")
+            .arg("row-group")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains("Row Group  Compressed  Uncompressed  Ratio")
+                .and(predicate::str::contains("\n2  "))
+                .and(predicate::str::contains("\n3  ").not()),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_pages() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("pages")
+            .arg("--column")
+            .arg("continent")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "File Name: data/cities.parquet\n\n\
+             Row Group 0, Column continent:\n\
+             Offset  Type        Encoding          Header  Compressed  Uncompressed  Values  Nulls  Min     Max\n     \
+             4  dictionary  PLAIN_DICTIONARY      13          27            27       2      -  -       -\n    \
+             44  data_v1     PLAIN_DICTIONARY      44           9             9       3      0  Europe  North America\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("pages")
+            .arg("--json")
+            .arg("--row-group")
+            .arg("1")
+            .arg("--column")
+            .arg("timeperiod")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "\"row_group\":1,\"column\":\"timeperiod\"",
+            ))
+            .stdout(predicate::str::contains("\"page_type\":\"dictionary\""))
+            .stdout(predicate::str::contains("\"row_group\":0").not());
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("pages")
+            .arg("--column")
+            .arg("country")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().code(2).stderr(predicate::str::contains(
+            "Column country is not a column of the file",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_page_index() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("page-index")
+            .arg("--summary")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "File Name: data/pems-indexed.snappy.parquet\n\
+             Column Index: 75 of 75 column chunks\n\
+             Offset Index: 75 of 75 column chunks\n\n\
+             File Name: data/cities.parquet\n\
+             Column Index: 0 of 3 column chunks\n\
+             Offset Index: 0 of 3 column chunks\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("page-index")
+            .arg("--row-group")
+            .arg("1")
+            .arg("--column")
+            .arg("timeperiod")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::ends_with(
+            "Row Group 1, Column timeperiod (ascending):\n\
+             Page  Offset  Compressed  First Row  Nulls  Min                  Max\n   \
+             0   16204         336          0      0  01/17/2016 08:20:27  01/17/2016 10:24:57\n   \
+             1   16540         368        250      0  01/17/2016 10:25:27  01/17/2016 12:30:57\n   \
+             2   16908         400        500      0  01/17/2016 12:31:27  01/17/2016 15:34:27\n   \
+             3   17308         400        750      0  01/17/2016 15:34:57  01/17/2016 17:50:27\n",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("page-index")
+            .arg("--json")
+            .arg("--column")
+            .arg("flow4")
+            .arg("--row-group")
+            .arg("0")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::contains(
+            "{\"offset\":8991,\"compressed_size\":28,\"first_row_index\":0,\
+             \"null_page\":true,\"null_count\":250,\"min\":null,\"max\":null}",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_bloom() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("bloom")
+            .arg("--column")
+            .arg("timeperiod")
+            .arg("--probe")
+            .arg("01/17/2016 10:25:27")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "File Name: data/pems-indexed.snappy.parquet\n\
+             Row Group  Column      Offset  Size  Algorithm    Hash    Compression   Contains 01/17/2016 10:25:27\n        \
+             0  timeperiod   31678  2064  SPLIT_BLOCK  XXHASH  UNCOMPRESSED  no\n        \
+             1  timeperiod   33742  2064  SPLIT_BLOCK  XXHASH  UNCOMPRESSED  maybe\n        \
+             2  timeperiod   35806  2064  SPLIT_BLOCK  XXHASH  UNCOMPRESSED  no\n\
+             01/17/2016 10:25:27 might be in 1 of 3 row groups\n",
+        );
+
+        // the footer is read with the prefetch, followed by the header of each filter
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("bloom")
+            .arg("--column")
+            .arg("timeperiod")
+            .arg("--verbose-io")
+            .arg("--prefetch")
+            .arg("8192")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("8384 bytes read, 4 seeks"));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("bloom")
+            .arg("--json")
+            .arg("--column")
+            .arg("id")
+            .arg("--probe")
+            .arg("1")
+            .arg(SCORES_1_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::contains(
+            "\"column\":\"id\",\"bloom_filter\":null,\"might_contain\":true",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("bloom")
+            .arg("--column")
+            .arg("flow1")
+            .arg("--probe")
+            .arg("x")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert()
+            .code(2)
+            .stderr(predicate::str::contains("Cannot probe column flow1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_dictionary() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("dictionary")
+            .arg("--column")
+            .arg("continent")
+            .arg("--column")
+            .arg("country.name")
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "File Name: data/cities.parquet\n\n\
+             Row Group 0, Column continent:\n\
+             Dictionary: 2 values (PLAIN_DICTIONARY), offset 4, size 27\n\
+             All 1 data pages use the dictionary\n\
+             Index  Value          Count\n    \
+             0  Europe             2\n    \
+             1  North America      1\n\n\
+             Row Group 0, Column country.name:\n\
+             Not dictionary encoded\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("dictionary")
+            .arg("--column")
+            .arg("word")
+            .arg("--limit")
+            .arg("2")
+            .arg(FALLBACK_PARQUET_PATH);
+        cmd.assert().success().stdout(predicate::str::ends_with(
+            "Dictionary: 9 values (PLAIN), offset 255, size 92\n\
+             Fell back to PLAIN after 3 of 4 data pages\n\
+             Index  Value   Count\n    \
+             0  apple       6\n    \
+             1  banana      6\n\
+             ... and 7 more values\n\
+             8 values in pages that fell back\n",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("dictionary")
+            .arg("--json")
+            .arg("--no-counts")
+            .arg("--column")
+            .arg("word")
+            .arg(FALLBACK_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("\"fell_back\":true"))
+            .stdout(predicate::str::contains(
+                "\"fallback_encodings\":[\"PLAIN\"]",
+            ))
+            .stdout(predicate::str::contains("\"count\"").not());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("verify")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .arg(CHECKSUMS_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains("File: data/checksums.parquet: valid")
+                .and(predicate::str::contains("page_crc: passed (8 checked)"))
+                .and(predicate::str::contains("row_counts: passed (3 checked)")),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_verify_corrupt() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let corrupt_path = dir.path().join("corrupt.parquet");
+        let mut bytes = std::fs::read(PEMS_INDEXED_PARQUET_PATH)?;
+        bytes[CORRUPT_PAGE_OFFSET..CORRUPT_PAGE_OFFSET + 64].fill(0xff);
+        std::fs::write(&corrupt_path, bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("verify").arg("--json").arg(&corrupt_path);
+        cmd.assert().failure().code(7).stdout(
+            predicate::str::contains(r#""valid":false"#)
+                .and(predicate::str::contains(
+                    r#""name":"page_headers","status":"failed""#,
+                ))
+                .and(predicate::str::contains(r#""column":"timeperiod""#)),
+        );
+
+        let mismatch_path = dir.path().join("mismatch.parquet");
+        let mut bytes = std::fs::read(CHECKSUMS_PARQUET_PATH)?;
+        bytes[CHECKSUMMED_PAGE_OFFSET] ^= 0xff;
+        std::fs::write(&mismatch_path, bytes)?;
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("verify").arg(&mismatch_path);
+        cmd.assert()
+            .failure()
+            .code(7)
+            .stdout(predicate::str::contains("page_crc: failed"))
+            .stderr(predicate::str::contains("1 of 1 files failed verification"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_recover() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let bytes = std::fs::read(PEMS_INDEXED_PARQUET_PATH)?;
+
+        // without the footer every row group can be recovered
+        let truncated_path = dir.path().join("truncated.parquet");
+        std::fs::write(&truncated_path, &bytes[..bytes.len() - 8])?;
+        let recovered_path = dir.path().join("recovered.parquet");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("recover")
+            .arg("--schema")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .arg("--output")
+            .arg(&recovered_path)
+            .arg(&truncated_path);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "Recovered 2693 rows in 3 row groups",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        let recovered = cmd
+            .arg("cat")
+            .arg("--json")
+            .arg("--quiet")
+            .arg(&recovered_path)
+            .output()?
+            .stdout;
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat")
+            .arg("--json")
+            .arg("--quiet")
+            .arg(PEMS_INDEXED_PARQUET_PATH);
+        cmd.assert().success().stdout(String::from_utf8(recovered)?);
+
+        // the output of schema --json holds the whole schema and the key value metadata
+        let schema_path = dir.path().join("schema.json");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        let schema = cmd
+            .arg("schema")
+            .arg("--json")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .output()?
+            .stdout;
+        std::fs::write(&schema_path, &schema)?;
+        let json_recovered_path = dir.path().join("json-recovered.parquet");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("recover")
+            .arg("-s")
+            .arg(&schema_path)
+            .arg("-o")
+            .arg(&json_recovered_path)
+            .arg(&truncated_path);
+        cmd.assert().success();
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema").arg("--json").arg(&json_recovered_path);
+        let expected: serde_json::Value = serde_json::from_slice(&schema)?;
+        let output = cmd.output()?.stdout;
+        let recovered: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(recovered["schema"], expected["schema"]);
+        assert_eq!(recovered["metadata"], expected["metadata"]);
+
+        // the schema can be given as the output of schema --arrow, and only the row
+        // groups that were written completely are recovered
+        let schema_path = dir.path().join("schema.json");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        let schema = cmd
+            .arg("schema")
+            .arg("--arrow")
+            .arg(PEMS_INDEXED_PARQUET_PATH)
+            .output()?
+            .stdout;
+        std::fs::write(&schema_path, schema)?;
+        std::fs::write(&truncated_path, &bytes[..CORRUPT_PAGE_OFFSET + 10000])?;
+        let partial_path = dir.path().join("partial.parquet");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("recover")
+            .arg("-s")
+            .arg(&schema_path)
+            .arg("-o")
+            .arg(&partial_path)
+            .arg(&truncated_path);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "Recovered 2000 rows in 2 row groups",
+        ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("rowcount").arg(&partial_path);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("2000 rows"));
+
+        // nothing is recovered with the schema of another file, whose pages are
+        // rejected before the decoders panic on them
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("recover")
+            .arg("-s")
+            .arg(CITIES_PARQUET_PATH)
+            .arg("-o")
+            .arg(dir.path().join("cities.parquet"))
+            .arg(&truncated_path);
+        cmd.assert().failure().code(5).stderr(
+            predicate::str::contains("No row groups could be recovered")
+                .and(predicate::str::contains("panicked").not()),
+        );
+
+        // records that span pages are counted once
+        let bytes = std::fs::read(SPANNING_RECORDS_PARQUET_PATH)?;
+        std::fs::write(&truncated_path, &bytes[..bytes.len() - 8])?;
+        let spanning_path = dir.path().join("spanning.parquet");
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("recover")
+            .arg("-s")
+            .arg(SPANNING_RECORDS_PARQUET_PATH)
+            .arg("-o")
+            .arg(&spanning_path)
+            .arg(&truncated_path);
+        cmd.assert()
+            .success()
+            .stderr(predicate::str::contains("Recovered 3 rows in 1 row groups"));
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("cat").arg("--quiet").arg(&spanning_path);
+        cmd.assert().success().stdout(
+            "{id: 1, values: [1, 2, 3]}\n\
+             {id: 2, values: [4, 5, 6]}\n\
+             {id: 3, values: [7, 8, 9]}\n",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_diff() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("diff")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert().failure().code(7).stdout(
+            "Schema: 0 removed, 0 added, 1 changed\n  \
+             ~ id: Int64 not null -> Int32 not null\n\
+             Key value metadata: 0 removed, 0 added, 1 changed\n  \
+             ~ source: v1 -> v2\n\
+             Row count: identical (4)\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("diff")
+            .arg("--data")
+            .arg("--limit")
+            .arg("1")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert().failure().code(7).stdout(
+            predicate::str::contains("Data: 0 removed, 0 added, 4 changed")
+                .and(predicate::str::contains(
+                    r#"~ row 0: id: 1 -> 2, name: "ada" -> "bob", score: 1.5 -> 2.25"#,
+                ))
+                .and(predicate::str::contains("... and 3 more")),
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("diff")
+            .arg("--data")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_1_PARQUET_PATH);
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::ends_with("Data: identical\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_diff_key() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("diff")
+            .arg("--key")
+            .arg("id")
+            .arg("--tolerance")
+            .arg("0.001")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .code(7)
+            .stdout(predicate::str::ends_with(
+                "Data: 1 removed, 1 added, 1 changed\n  \
+             ~ id=3: name: \"cy\" -> \"cyd\"\n  \
+             + id=5: {\"id\":5,\"name\":\"eve\",\"score\":5.0}\n  \
+             - id=4: {\"id\":4,\"name\":\"dee\",\"score\":4.0}\n",
+            ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("diff")
+            .arg("--key")
+            .arg("id,missing")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr(predicate::str::contains("Key column missing"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_schema_diff() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema-diff")
+            .arg(SCORES_2_PARQUET_PATH)
+            .arg(SCORES_1_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            "data/scores-2.parquet -> data/scores-1.parquet: backward compatible\n  \
+             id: type widened from INT32 to INT64 (backward compatible)\n",
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema-diff")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .code(7)
+            .stdout(predicate::str::contains("narrowed from INT64 to INT32"))
+            .stderr(predicate::str::contains(
+                "1 schemas are not backward compatible",
+            ));
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema-diff")
+            .arg("--require")
+            .arg("forward")
+            .arg("--json")
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains(
+                r#""compatibility":"forward","changes":[{"column":"id""#,
+            )
+            .and(predicate::str::contains(
+                r#""compatibility":"full","changes":[]"#,
+            )),
+        );
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("schema-diff")
+            .arg("--require")
+            .arg("full")
+            .arg(SIMPLE_PARQUET_PATH)
+            .arg(CITIES_PARQUET_PATH);
+        cmd.assert().success().stdout(
+            predicate::str::contains("foo: removed optional column of type INT32")
+                .and(predicate::str::contains("continent: added optional column")),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_validate() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let schema_path = dir.path().join("schema.json");
+        let output = Command::cargo_bin("pqrs")?
+            .arg("schema")
+            .arg("--arrow")
+            .arg(SCORES_1_PARQUET_PATH)
+            .output()?;
+        std::fs::write(&schema_path, output.stdout)?;
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("validate")
+            .arg("--schema")
+            .arg(&schema_path)
+            .arg(SCORES_1_PARQUET_PATH)
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .code(7)
+            .stdout(
+                "File: data/scores-1.parquet: valid\n\
+                 File: data/scores-2.parquet: invalid\n  \
+                 id: has type Int32 but the contract expects Int64\n",
+            )
+            .stderr(predicate::str::contains("1 of 2 files failed validation"));
+
+        // the contract can be read from stdin
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("validate")
+            .arg("--schema")
+            .arg("-")
+            .arg(SCORES_1_PARQUET_PATH)
+            .write_stdin(std::fs::read(&schema_path)?);
+        cmd.assert()
+            .success()
+            .stdout("File: data/scores-1.parquet: valid\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_validate_constraints() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let contract_path = dir.path().join("contract.json");
+        let contract = r#"{
+            "columns": {
+                "id": {"nullable": false, "unique": true, "min": 1, "max": 4},
+                "name": {"pattern": "[a-z]{2,3}", "allowed": ["ada", "bob", "cy", "dee"]},
+                "rank": {"nullable": false}
+            }
+        }"#;
+        std::fs::write(&contract_path, contract)?;
+
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("validate")
+            .arg("--schema")
+            .arg(&contract_path)
+            .arg("--json")
+            .arg(SCORES_2_PARQUET_PATH);
+        cmd.assert().failure().code(7).stdout(
+            predicate::str::contains(
+                r#"{"column":"rank","check":"schema","message":"missing column"}"#,
+            )
+            .and(predicate::str::contains(
+                r#"{"column":"id","check":"max","message":"1 value above 4, e.g. 5"}"#,
+            ))
+            .and(predicate::str::contains(
+                r#""check":"allowed","message":"2 values"#,
+            )),
+        );
+
+        std::fs::write(&contract_path, r#"{"columns": {"name": {"pattern": "("}}}"#)?;
+        let mut cmd = Command::cargo_bin("pqrs")?;
+        cmd.arg("validate")
+            .arg("--schema")
+            .arg(&contract_path)
+            .arg(SCORES_1_PARQUET_PATH);
+        cmd.assert()
+            .failure()
+            .code(2)
+            .stderr(predicate::str::contains("Invalid contract"));
 
         Ok(())
     }